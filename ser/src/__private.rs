@@ -0,0 +1,120 @@
+//! Helpers for code generated by the derives. Not public API.
+
+/// The sum of `sizes`, or `None` if any of them is `None`.
+pub const fn fixed_sum(sizes: &[Option<usize>]) -> Option<usize> {
+    let mut total = 0usize;
+    let mut i = 0;
+    while i < sizes.len() {
+        total = match sizes[i] {
+            Some(size) => match total.checked_add(size) {
+                Some(total) => total,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(total)
+}
+
+/// The common value of `sizes`, or `None` if they differ, any of them is
+/// `None`, or there are none.
+pub const fn fixed_same(sizes: &[Option<usize>]) -> Option<usize> {
+    let first = match sizes {
+        [Some(first), ..] => *first,
+        _ => return None,
+    };
+    let mut i = 1;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) if size == first => {}
+            _ => return None,
+        }
+        i += 1;
+    }
+    Some(first)
+}
+
+/// `FIXED_SIZE` when values still take that many bytes with `options`.
+pub fn fixed_in(size: Option<usize>, options: crate::Options) -> Option<usize> {
+    size.filter(|_| options.fixed_width())
+}
+
+/// The options of the numbers a derived type always writes at a fixed width.
+pub fn fixed(options: crate::Options) -> crate::Options {
+    options.fixed()
+}
+
+/// The number of zero bytes that bring `offset` up to a multiple of `align`.
+pub const fn padding(offset: usize, align: usize) -> usize {
+    (align - offset % align) % align
+}
+
+/// `size` rounded up to a multiple of `align`, or `None` if `size` is.
+pub const fn fixed_pad(size: Option<usize>, align: usize) -> Option<usize> {
+    match size {
+        Some(size) => size.checked_add(padding(size, align)),
+        None => None,
+    }
+}
+
+/// The offset `fixed_sum` gave for an `OFFSET_<FIELD>` constant, or a compile
+/// error with `message` when using a constant whose offset is not fixed.
+pub const fn fixed_offset(offset: Option<usize>, message: &str) -> usize {
+    match offset {
+        Some(offset) => offset,
+        None => panic!("{}", message),
+    }
+}
+
+/// Zero-fill the padding that aligns `offset` in `out` and return its length.
+pub fn write_padding(out: &mut [u8], offset: usize, align: usize) -> usize {
+    let pad = padding(offset, align);
+    out[offset..offset + pad].fill(0);
+    pad
+}
+
+/// Skip the padding that aligns `offset` in `buf` and return the offset
+/// after it.
+pub fn skip_padding(buf: &[u8], offset: usize, align: usize) -> Result<usize, crate::DecodeError> {
+    let end = offset + padding(offset, align);
+    if buf.len() < end {
+        return Err(crate::DecodeError::UnexpectedEof);
+    }
+    Ok(end)
+}
+
+/// The bits `Bits::to_bits` gave for a `#[disk(bits = width)]` field,
+/// shifted to its place in the group.
+pub fn pack(bits: Option<u64>, shift: u32, width: u32) -> Result<u64, crate::EncodeError> {
+    match bits {
+        Some(bits) => Ok(bits << shift),
+        None => Err(crate::EncodeError::BitsOverflow { bits: width }),
+    }
+}
+
+/// The bits of the `#[disk(bits = width)]` field at `shift` in a group.
+pub fn unpack(group: u64, shift: u32, width: u32) -> u64 {
+    (group >> shift) & (u64::MAX >> (64 - width))
+}
+
+/// The string encoded at the start of `buf`, borrowed from it, and the number
+/// of bytes it occupies.
+pub fn borrow_str(
+    buf: &[u8],
+    options: crate::Options,
+) -> Result<(&str, usize), crate::DecodeError> {
+    let (bytes, n) = crate::impls::read_bytes(buf, options)?;
+    let value = std::str::from_utf8(bytes).map_err(|_| crate::DecodeError::InvalidUtf8)?;
+    Ok((value, n))
+}
+
+/// The elements of the `Vec<u8>` encoded at the start of `buf` with
+/// fixed-width integers, borrowed from it, and the number of bytes it
+/// occupies.
+pub fn borrow_bytes(
+    buf: &[u8],
+    options: crate::Options,
+) -> Result<(&[u8], usize), crate::DecodeError> {
+    crate::impls::read_bytes(buf, options)
+}
@@ -0,0 +1,127 @@
+use crate::DecodeError;
+
+/// A value that can be stored in a few bits of a `#[disk(bits = N)]` group.
+///
+/// Implemented for `bool` and the integer types up to 64 bits, and derived
+/// for enums without fields by [`ser_derive::Bits`], which stores the tag.
+///
+/// Signed integers are stored in two's complement and sign-extended when
+/// read back, and a value too wide for its field fails the write:
+///
+/// ```
+/// use ser::{DiskView, EncodeError, FromDisk, SizedOnDisk, ToDisk};
+///
+/// #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk, DiskView)]
+/// #[disk(endian = "big")]
+/// struct Header {
+///     #[disk(bits = 3)]
+///     delta: i8,
+///     #[disk(bits = 5)]
+///     level: u8,
+///     count: u8,
+///     #[disk(bits = 12)]
+///     offset: i16,
+///     #[disk(bits = 1)]
+///     dirty: bool,
+/// }
+///
+/// assert_eq!(Header::FIXED_SIZE, Some(4));
+/// assert_eq!((Header::OFFSET_OFFSET, Header::SIZE_DIRTY), (2, 2));
+/// let header = Header {
+///     delta: -3,
+///     level: 17,
+///     count: 9,
+///     offset: -2048,
+///     dirty: true,
+/// };
+/// let mut buf = vec![0; header.size()];
+/// assert_eq!(header.write_to(&mut buf), Ok(4));
+/// assert_eq!(buf, [0b10001_101, 9, 0b1_1000, 0]);
+/// assert_eq!(Header::read_from(&buf), Ok((header, 4)));
+///
+/// let view = HeaderRef::new(&buf).unwrap();
+/// assert_eq!((view.delta(), view.level(), view.count()), (-3, 17, 9));
+/// assert_eq!((view.offset(), view.dirty()), (-2048, true));
+///
+/// for (delta, offset, bits) in [(4, 0, 3), (-5, 0, 3), (0, 2048, 12)] {
+///     let header = Header { delta, level: 0, count: 0, offset, dirty: false };
+///     assert_eq!(
+///         header.write_to(&mut buf),
+///         Err(EncodeError::BitsOverflow { bits })
+///     );
+/// }
+/// ```
+pub trait Bits: Sized {
+    /// The low `width` bits that represent `self`, or `None` if `self` does
+    /// not fit in `width` bits.
+    fn to_bits(&self, width: u32) -> Option<u64>;
+
+    /// The value represented by the low `width` bits of `bits`; the others are
+    /// zero.
+    fn from_bits(bits: u64, width: u32) -> Result<Self, DecodeError>;
+}
+
+impl Bits for bool {
+    #[inline]
+    fn to_bits(&self, _width: u32) -> Option<u64> {
+        Some(*self as u64)
+    }
+
+    #[inline]
+    fn from_bits(bits: u64, _width: u32) -> Result<Self, DecodeError> {
+        match bits {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidBool(bits as u8)),
+        }
+    }
+}
+
+macro_rules! unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Bits for $ty {
+                #[inline]
+                fn to_bits(&self, width: u32) -> Option<u64> {
+                    let bits = *self as u64;
+                    (width >= 64 || bits >> width == 0).then_some(bits)
+                }
+
+                #[inline]
+                fn from_bits(bits: u64, _width: u32) -> Result<Self, DecodeError> {
+                    <$ty>::try_from(bits).map_err(|_| DecodeError::IntegerOverflow)
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8, u16, u32, u64);
+
+// Signed integers are stored in two's complement of the field's width.
+macro_rules! signed {
+    ($($ty:ty),*) => {
+        $(
+            impl Bits for $ty {
+                #[inline]
+                fn to_bits(&self, width: u32) -> Option<u64> {
+                    let value = *self as i64;
+                    let unused = 64 - width;
+                    // The value fits if dropping the unused high bits and
+                    // sign-extending back gives it again.
+                    let fits = (value << unused) >> unused == value;
+                    fits.then_some((value as u64) << unused >> unused)
+                }
+
+                #[inline]
+                fn from_bits(bits: u64, width: u32) -> Result<Self, DecodeError> {
+                    let unused = 64 - width;
+                    let value = ((bits << unused) as i64) >> unused;
+                    <$ty>::try_from(value).map_err(|_| DecodeError::IntegerOverflow)
+                }
+            }
+        )*
+    };
+}
+
+signed!(i8, i16, i32, i64);
@@ -0,0 +1,184 @@
+//! The checksums `#[disk(checksum = "...")]` appends to an encoding.
+//!
+//! Both are implemented here without dependencies and match the reference
+//! implementations bit for bit, so records can be checked by other tools.
+//!
+//! The checksum covers the bytes before it and comes ahead of any `align`
+//! padding, which it does not cover. Decoding bytes that do not match it
+//! fails:
+//!
+//! ```
+//! use ser::checksum::{crc32c, xxh64};
+//! use ser::{DecodeError, FromDisk, SizedOnDisk, ToDisk};
+//!
+//! #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+//! #[disk(checksum = "crc32c")]
+//! struct Block {
+//!     id: u32,
+//!     name: String,
+//! }
+//!
+//! #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+//! #[disk(checksum = "xxh64", align = 8)]
+//! enum Op {
+//!     Put(u32),
+//!     Clear,
+//! }
+//!
+//! let block = Block { id: 1, name: "ab".into() };
+//! let mut buf = vec![0; block.size()];
+//! assert_eq!(block.write_to(&mut buf), Ok(14));
+//! assert_eq!(buf[10..], crc32c(&buf[..10]).to_le_bytes());
+//! assert_eq!(Block::read_from(&buf), Ok((block, 14)));
+//!
+//! buf[8] ^= 1;
+//! assert_eq!(
+//!     Block::read_from(&buf),
+//!     Err(DecodeError::ChecksumMismatch {
+//!         stored: crc32c(&[1, 0, 0, 0, 2, 0, 0, 0, b'a', b'b']).into(),
+//!         computed: crc32c(&buf[..10]).into(),
+//!     })
+//! );
+//!
+//! let op = Op::Put(7);
+//! assert_eq!(Op::FIXED_SIZE, Some(16));
+//! let mut buf = [0; 16];
+//! assert_eq!(op.write_to(&mut buf), Ok(16));
+//! assert_eq!(buf[..5], [0, 7, 0, 0, 0]);
+//! assert_eq!(buf[5..13], xxh64(&buf[..5], 0).to_le_bytes());
+//! buf[15] = 0xff;
+//! assert_eq!(Op::read_from(&buf), Ok((op, 16)));
+//!
+//! buf[1] = 8;
+//! assert_eq!(
+//!     Op::read_from(&buf),
+//!     Err(DecodeError::ChecksumMismatch {
+//!         stored: xxh64(&[0, 7, 0, 0, 0], 0),
+//!         computed: xxh64(&buf[..5], 0),
+//!     })
+//! );
+//! ```
+
+// CRC-32C uses the Castagnoli polynomial, bit-reflected.
+const CRC32C_POLY: u32 = 0x82f6_3b78;
+
+// The CRC of every byte value, for processing a byte at a time.
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32C_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The CRC-32C (Castagnoli) checksum of `bytes`, as used by iSCSI and ext4.
+///
+/// ```
+/// assert_eq!(ser::checksum::crc32c(b"123456789"), 0xe306_9283);
+/// ```
+pub fn crc32c(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = (crc >> 8) ^ CRC32C_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize];
+    }
+    !crc
+}
+
+const PRIME64_1: u64 = 0x9e37_79b1_85eb_ca87;
+const PRIME64_2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const PRIME64_3: u64 = 0x1656_67b1_9e37_79f9;
+const PRIME64_4: u64 = 0x85eb_ca77_c2b2_ae63;
+const PRIME64_5: u64 = 0x27d4_eb2f_1656_67c5;
+
+/// The 64-bit xxHash (XXH64) of `bytes` with the given seed.
+///
+/// ```
+/// assert_eq!(ser::checksum::xxh64(b"", 0), 0xef46_db37_51d8_e999);
+/// assert_eq!(ser::checksum::xxh64(b"abc", 0), 0x44bc_2cf5_ad77_0999);
+/// assert_eq!(
+///     ser::checksum::xxh64(b"Nobody inspects the spammish repetition", 0),
+///     0xfbce_a83c_8a37_8bf1,
+/// );
+/// ```
+pub fn xxh64(bytes: &[u8], seed: u64) -> u64 {
+    let mut rest = bytes;
+    let mut hash = if bytes.len() >= 32 {
+        let mut lanes = [
+            seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+            seed.wrapping_add(PRIME64_2),
+            seed,
+            seed.wrapping_sub(PRIME64_1),
+        ];
+        while rest.len() >= 32 {
+            for (lane, word) in lanes.iter_mut().zip(rest.chunks_exact(8)) {
+                *lane = xxh64_round(*lane, read_u64(word));
+            }
+            rest = &rest[32..];
+        }
+        let mut hash = lanes[0]
+            .rotate_left(1)
+            .wrapping_add(lanes[1].rotate_left(7))
+            .wrapping_add(lanes[2].rotate_left(12))
+            .wrapping_add(lanes[3].rotate_left(18));
+        for lane in lanes {
+            hash = (hash ^ xxh64_round(0, lane))
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+        }
+        hash
+    } else {
+        seed.wrapping_add(PRIME64_5)
+    };
+    hash = hash.wrapping_add(bytes.len() as u64);
+
+    while rest.len() >= 8 {
+        hash ^= xxh64_round(0, read_u64(rest));
+        hash = hash
+            .rotate_left(27)
+            .wrapping_mul(PRIME64_1)
+            .wrapping_add(PRIME64_4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        let word = u32::from_le_bytes(rest[..4].try_into().unwrap());
+        hash ^= u64::from(word).wrapping_mul(PRIME64_1);
+        hash = hash
+            .rotate_left(23)
+            .wrapping_mul(PRIME64_2)
+            .wrapping_add(PRIME64_3);
+        rest = &rest[4..];
+    }
+    for &byte in rest {
+        hash ^= u64::from(byte).wrapping_mul(PRIME64_5);
+        hash = hash.rotate_left(11).wrapping_mul(PRIME64_1);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(PRIME64_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(PRIME64_3);
+    hash ^= hash >> 32;
+    hash
+}
+
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+use std::{fmt, io};
+
+/// The reasons [`FromDisk::read_from`](crate::FromDisk::read_from) can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The buffer ended before the value did.
+    UnexpectedEof,
+    /// An enum or `Option` tag that names no variant.
+    InvalidTag {
+        /// The type being decoded.
+        ty: &'static str,
+        /// The tag that was read.
+        tag: i128,
+    },
+    /// A `bool` byte other than `0` or `1`.
+    InvalidBool(u8),
+    /// A `char` that is not a Unicode scalar value.
+    InvalidChar(u32),
+    /// A string that is not valid UTF-8.
+    InvalidUtf8,
+    /// A stored integer that does not fit the type it is read into.
+    IntegerOverflow,
+    /// The checksum stored after a value does not match the one computed
+    /// over its bytes.
+    ChecksumMismatch {
+        /// The checksum that was read.
+        stored: u64,
+        /// The checksum of the bytes that were read.
+        computed: u64,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::UnexpectedEof => f.write_str("unexpected end of buffer"),
+            DecodeError::InvalidTag { ty, tag } => write!(f, "invalid tag {} for {}", tag, ty),
+            DecodeError::InvalidBool(byte) => write!(f, "invalid bool byte {}", byte),
+            DecodeError::InvalidChar(value) => write!(f, "invalid char {:#x}", value),
+            DecodeError::InvalidUtf8 => f.write_str("string is not valid UTF-8"),
+            DecodeError::IntegerOverflow => f.write_str("integer does not fit its type"),
+            DecodeError::ChecksumMismatch { stored, computed } => write!(
+                f,
+                "checksum mismatch: stored {:#x}, computed {:#x}",
+                stored, computed
+            ),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// The reasons [`ToDisk::write_to`](crate::ToDisk::write_to) can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// A string or collection longer than its length prefix can count.
+    LengthOverflow {
+        /// The length that was to be written.
+        len: usize,
+        /// The largest length the prefix holds.
+        max: u64,
+    },
+    /// A value of a `#[disk(bits = N)]` field that does not fit in `N` bits.
+    BitsOverflow {
+        /// The width of the field.
+        bits: u32,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EncodeError::LengthOverflow { len, max } => {
+                write!(
+                    f,
+                    "length {} exceeds the length prefix maximum {}",
+                    len, max
+                )
+            }
+            EncodeError::BitsOverflow { bits } => {
+                write!(f, "value does not fit in {} bits", bits)
+            }
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+impl From<EncodeError> for io::Error {
+    fn from(error: EncodeError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
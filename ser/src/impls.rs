@@ -0,0 +1,865 @@
+use std::marker::PhantomData;
+
+use crate::__private::fixed_sum;
+use crate::{
+    DecodeError, EncodeError, Endian, FromDisk, IntEncoding, LenPrefix, Options, SizedOnDisk,
+    ToDisk,
+};
+
+// Width of the length prefix written before a string or vector of `len`.
+fn len_size(len: usize, options: Options) -> usize {
+    match options.len {
+        LenPrefix::U8 => 1,
+        LenPrefix::U16 => 2,
+        LenPrefix::U32 => 4,
+        LenPrefix::Varint => varint_len(len as u128),
+    }
+}
+
+// `start` plus each of `sizes`, or `None` if any of them is `None` or the
+// total overflows.
+fn checked_sum(start: usize, sizes: impl IntoIterator<Item = Option<usize>>) -> Option<usize> {
+    sizes
+        .into_iter()
+        .try_fold(start, |total, size| total.checked_add(size?))
+}
+
+// Write the length prefix of a string or vector.
+fn write_len(len: usize, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+    let overflow = |max: u64| EncodeError::LengthOverflow { len, max };
+    let fixed = options.fixed();
+    match options.len {
+        LenPrefix::U8 => u8::try_from(len)
+            .map_err(|_| overflow(u8::MAX.into()))?
+            .write_in(out, fixed),
+        LenPrefix::U16 => u16::try_from(len)
+            .map_err(|_| overflow(u16::MAX.into()))?
+            .write_in(out, fixed),
+        LenPrefix::U32 => u32::try_from(len)
+            .map_err(|_| overflow(u32::MAX.into()))?
+            .write_in(out, fixed),
+        LenPrefix::Varint => Ok(write_varint(len as u128, out)),
+    }
+}
+
+// Read the length prefix of a string or vector.
+fn read_len(buf: &[u8], options: Options) -> Result<(usize, usize), DecodeError> {
+    let fixed = options.fixed();
+    let (len, n) = match options.len {
+        LenPrefix::U8 => u8::read_in(buf, fixed).map(|(len, n)| (len.into(), n))?,
+        LenPrefix::U16 => u16::read_in(buf, fixed).map(|(len, n)| (len.into(), n))?,
+        LenPrefix::U32 => u32::read_in(buf, fixed).map(|(len, n)| (len.into(), n))?,
+        LenPrefix::Varint => read_varint(buf)?,
+    };
+    let len = usize::try_from(len).map_err(|_| DecodeError::IntegerOverflow)?;
+    Ok((len, n))
+}
+
+// The bytes of a string after its length prefix, and the offset just past
+// them. A length that runs past the end of `buf`, even one too large to add
+// to the offset, is reported as `UnexpectedEof`.
+pub(crate) fn read_bytes(buf: &[u8], options: Options) -> Result<(&[u8], usize), DecodeError> {
+    let (len, n) = read_len(buf, options)?;
+    let end = n.checked_add(len).ok_or(DecodeError::UnexpectedEof)?;
+    let bytes = buf.get(n..end).ok_or(DecodeError::UnexpectedEof)?;
+    Ok((bytes, end))
+}
+
+// The first `N` bytes of `buf`.
+fn take<const N: usize>(buf: &[u8]) -> Result<[u8; N], DecodeError> {
+    match buf.get(..N) {
+        Some(bytes) => Ok(bytes.try_into().unwrap()),
+        None => Err(DecodeError::UnexpectedEof),
+    }
+}
+
+// The unsigned value a number is written as under `IntEncoding::Varint` and
+// `IntEncoding::Zigzag`. Floats have no variable-length form.
+trait Varint: Sized {
+    const VARINT: bool = true;
+
+    fn to_varint(self, int: IntEncoding) -> u128;
+
+    fn from_varint(value: u128, int: IntEncoding) -> Option<Self>;
+}
+
+macro_rules! unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Varint for $ty {
+                #[inline]
+                fn to_varint(self, _int: IntEncoding) -> u128 {
+                    self as u128
+                }
+
+                #[inline]
+                fn from_varint(value: u128, _int: IntEncoding) -> Option<Self> {
+                    <$ty>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8, u16, u32, u64, u128);
+
+macro_rules! signed {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+            impl Varint for $ty {
+                #[inline]
+                fn to_varint(self, int: IntEncoding) -> u128 {
+                    let bits = match int {
+                        IntEncoding::Zigzag => (self << 1) ^ (self >> (<$ty>::BITS - 1)),
+                        _ => self,
+                    };
+                    bits as $unsigned as u128
+                }
+
+                #[inline]
+                fn from_varint(value: u128, int: IntEncoding) -> Option<Self> {
+                    let bits = <$unsigned>::try_from(value).ok()?;
+                    Some(match int {
+                        IntEncoding::Zigzag => (bits >> 1) as $ty ^ -((bits & 1) as $ty),
+                        _ => bits as $ty,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+macro_rules! float {
+    ($($ty:ty),*) => {
+        $(
+            impl Varint for $ty {
+                const VARINT: bool = false;
+
+                fn to_varint(self, _int: IntEncoding) -> u128 {
+                    unreachable!("floats are always fixed width")
+                }
+
+                fn from_varint(_value: u128, _int: IntEncoding) -> Option<Self> {
+                    unreachable!("floats are always fixed width")
+                }
+            }
+        )*
+    };
+}
+
+float!(f32, f64);
+
+// The number of bytes LEB128 takes for `value`.
+fn varint_len(value: u128) -> usize {
+    let bits = 128 - value.leading_zeros() as usize;
+    bits.div_ceil(7).max(1)
+}
+
+fn write_varint(mut value: u128, out: &mut [u8]) -> usize {
+    let mut n = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[n] = byte;
+            return n + 1;
+        }
+        out[n] = byte | 0x80;
+        n += 1;
+    }
+}
+
+fn read_varint(buf: &[u8]) -> Result<(u128, usize), DecodeError> {
+    let mut value = 0u128;
+    for (i, &byte) in buf.iter().enumerate() {
+        let shift = 7 * i as u32;
+        let bits = u128::from(byte & 0x7f);
+        // Bits shifted past the top, including by an overlong encoding,
+        // cannot belong to any integer.
+        if shift >= u128::BITS || (bits << shift) >> shift != bits {
+            return Err(DecodeError::IntegerOverflow);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(DecodeError::UnexpectedEof)
+}
+
+macro_rules! number {
+    ($($ty:ty),*) => {
+        $(
+            impl SizedOnDisk for $ty {
+                const FIXED_SIZE: Option<usize> = Some(core::mem::size_of::<$ty>());
+
+                #[inline]
+                fn size(&self) -> usize {
+                    core::mem::size_of::<$ty>()
+                }
+
+                #[inline]
+                fn size_in(&self, options: Options) -> usize {
+                    if <$ty>::VARINT && options.int != IntEncoding::Fixed {
+                        varint_len(self.to_varint(options.int))
+                    } else {
+                        core::mem::size_of::<$ty>()
+                    }
+                }
+
+                #[inline]
+                fn checked_size_in(&self, options: Options) -> Option<usize> {
+                    Some(self.size_in(options))
+                }
+            }
+
+            impl ToDisk for $ty {
+                #[inline]
+                fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+                    self.write_in(out, Options::DEFAULT)
+                }
+
+                #[inline]
+                fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+                    if <$ty>::VARINT && options.int != IntEncoding::Fixed {
+                        return Ok(write_varint(self.to_varint(options.int), out));
+                    }
+                    let bytes = match options.endian {
+                        Endian::Little => self.to_le_bytes(),
+                        Endian::Big => self.to_be_bytes(),
+                        Endian::Native => self.to_ne_bytes(),
+                    };
+                    out[..bytes.len()].copy_from_slice(&bytes);
+                    Ok(bytes.len())
+                }
+            }
+
+            impl FromDisk for $ty {
+                #[inline]
+                fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    Self::read_in(buf, Options::DEFAULT)
+                }
+
+                #[inline]
+                fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+                    if <$ty>::VARINT && options.int != IntEncoding::Fixed {
+                        let (value, n) = read_varint(buf)?;
+                        let value = <$ty>::from_varint(value, options.int)
+                            .ok_or(DecodeError::IntegerOverflow)?;
+                        return Ok((value, n));
+                    }
+                    let bytes = take(buf)?;
+                    let value = match options.endian {
+                        Endian::Little => <$ty>::from_le_bytes(bytes),
+                        Endian::Big => <$ty>::from_be_bytes(bytes),
+                        Endian::Native => <$ty>::from_ne_bytes(bytes),
+                    };
+                    Ok((value, bytes.len()))
+                }
+            }
+        )*
+    };
+}
+
+number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+// Pointer-sized integers are stored as their 64-bit counterparts.
+macro_rules! pointer_sized {
+    ($($ty:ty => $as:ty),*) => {
+        $(
+            impl SizedOnDisk for $ty {
+                const FIXED_SIZE: Option<usize> = Some(core::mem::size_of::<$as>());
+
+                #[inline]
+                fn size(&self) -> usize {
+                    core::mem::size_of::<$as>()
+                }
+
+                #[inline]
+                fn size_in(&self, options: Options) -> usize {
+                    (*self as $as).size_in(options)
+                }
+
+                #[inline]
+                fn checked_size_in(&self, options: Options) -> Option<usize> {
+                    Some(self.size_in(options))
+                }
+            }
+
+            impl ToDisk for $ty {
+                #[inline]
+                fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+                    self.write_in(out, Options::DEFAULT)
+                }
+
+                #[inline]
+                fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+                    (*self as $as).write_in(out, options)
+                }
+            }
+
+            impl FromDisk for $ty {
+                #[inline]
+                fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    Self::read_in(buf, Options::DEFAULT)
+                }
+
+                #[inline]
+                fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+                    let (value, n) = <$as>::read_in(buf, options)?;
+                    let value = <$ty>::try_from(value).map_err(|_| DecodeError::IntegerOverflow)?;
+                    Ok((value, n))
+                }
+            }
+        )*
+    };
+}
+
+pointer_sized!(usize => u64, isize => i64);
+
+impl SizedOnDisk for bool {
+    const FIXED_SIZE: Option<usize> = Some(1);
+
+    #[inline]
+    fn size(&self) -> usize {
+        1
+    }
+}
+
+impl ToDisk for bool {
+    #[inline]
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        (*self as u8).write_to(out)
+    }
+}
+
+impl FromDisk for bool {
+    #[inline]
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        match u8::read_from(buf)? {
+            (0, n) => Ok((false, n)),
+            (1, n) => Ok((true, n)),
+            (byte, _) => Err(DecodeError::InvalidBool(byte)),
+        }
+    }
+}
+
+impl SizedOnDisk for char {
+    const FIXED_SIZE: Option<usize> = Some(4);
+
+    #[inline]
+    fn size(&self) -> usize {
+        4
+    }
+}
+
+impl ToDisk for char {
+    #[inline]
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.write_in(out, Options::DEFAULT)
+    }
+
+    #[inline]
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        (*self as u32).write_in(out, options.fixed())
+    }
+}
+
+impl FromDisk for char {
+    #[inline]
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    #[inline]
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let (value, n) = u32::read_in(buf, options.fixed())?;
+        let value = char::from_u32(value).ok_or(DecodeError::InvalidChar(value))?;
+        Ok((value, n))
+    }
+}
+
+impl SizedOnDisk for () {
+    const FIXED_SIZE: Option<usize> = Some(0);
+
+    #[inline]
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+impl ToDisk for () {
+    #[inline]
+    fn write_to(&self, _out: &mut [u8]) -> Result<usize, EncodeError> {
+        Ok(0)
+    }
+}
+
+impl FromDisk for () {
+    #[inline]
+    fn read_from(_buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Ok(((), 0))
+    }
+}
+
+impl<T: ?Sized> SizedOnDisk for PhantomData<T> {
+    const FIXED_SIZE: Option<usize> = Some(0);
+
+    #[inline]
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> ToDisk for PhantomData<T> {
+    #[inline]
+    fn write_to(&self, _out: &mut [u8]) -> Result<usize, EncodeError> {
+        Ok(0)
+    }
+}
+
+impl<T: ?Sized> FromDisk for PhantomData<T> {
+    #[inline]
+    fn read_from(_buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Ok((PhantomData, 0))
+    }
+}
+
+impl SizedOnDisk for str {
+    fn size(&self) -> usize {
+        self.size_in(Options::DEFAULT)
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        len_size(self.len(), options) + self.len()
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        self.checked_size_in(Options::DEFAULT)
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        len_size(self.len(), options).checked_add(self.len())
+    }
+}
+
+impl ToDisk for str {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.write_in(out, Options::DEFAULT)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        let n = write_len(self.len(), out, options)?;
+        out[n..n + self.len()].copy_from_slice(self.as_bytes());
+        Ok(n + self.len())
+    }
+}
+
+impl SizedOnDisk for String {
+    fn size(&self) -> usize {
+        self.as_str().size()
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        self.as_str().size_in(options)
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        self.as_str().checked_size()
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        self.as_str().checked_size_in(options)
+    }
+}
+
+impl ToDisk for String {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.as_str().write_to(out)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        self.as_str().write_in(out, options)
+    }
+}
+
+impl FromDisk for String {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let (bytes, n) = read_bytes(buf, options)?;
+        let value = std::str::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)?;
+        Ok((value.to_owned(), n))
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        let (bytes, n) = read_bytes(buf, options)?;
+        std::str::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)?;
+        Ok(n)
+    }
+}
+
+impl<T: SizedOnDisk> SizedOnDisk for Vec<T> {
+    fn size(&self) -> usize {
+        len_size(self.len(), Options::DEFAULT) + self.iter().map(SizedOnDisk::size).sum::<usize>()
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        let items = self.iter().map(|item| item.size_in(options));
+        len_size(self.len(), options) + items.sum::<usize>()
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        let items = self.iter().map(SizedOnDisk::checked_size);
+        checked_sum(len_size(self.len(), Options::DEFAULT), items)
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        let items = self.iter().map(|item| item.checked_size_in(options));
+        checked_sum(len_size(self.len(), options), items)
+    }
+}
+
+impl<T: ToDisk> ToDisk for Vec<T> {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.write_in(out, Options::DEFAULT)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        let mut n = write_len(self.len(), out, options)?;
+        for item in self {
+            n += item.write_in(&mut out[n..], options)?;
+        }
+        Ok(n)
+    }
+}
+
+impl<T: FromDisk> FromDisk for Vec<T> {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let (len, mut n) = read_len(buf, options)?;
+        // Cap the reservation by the bytes left so a corrupt length cannot
+        // allocate a huge buffer up front.
+        let mut items = Vec::with_capacity(len.min(buf.len() - n));
+        for _ in 0..len {
+            let (item, m) = T::read_in(&buf[n..], options)?;
+            items.push(item);
+            n += m;
+        }
+        Ok((items, n))
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        let (len, mut n) = read_len(buf, options)?;
+        for _ in 0..len {
+            // An element that takes no bytes is followed by more of the
+            // same, read from the same place, so the rest need no checking
+            // and a corrupt count cannot keep the loop going.
+            match T::skip_in(&buf[n..], options)? {
+                0 => break,
+                m => n += m,
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<T: SizedOnDisk> SizedOnDisk for Option<T> {
+    fn size(&self) -> usize {
+        1 + self.as_ref().map_or(0, SizedOnDisk::size)
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        1 + self.as_ref().map_or(0, |value| value.size_in(options))
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        checked_sum(1, self.as_ref().map(SizedOnDisk::checked_size))
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        checked_sum(1, self.as_ref().map(|value| value.checked_size_in(options)))
+    }
+}
+
+impl<T: ToDisk> ToDisk for Option<T> {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.write_in(out, Options::DEFAULT)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        match self {
+            None => 0u8.write_to(out),
+            Some(value) => {
+                let n = 1u8.write_to(out)?;
+                Ok(n + value.write_in(&mut out[n..], options)?)
+            }
+        }
+    }
+}
+
+impl<T: FromDisk> FromDisk for Option<T> {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        match u8::read_from(buf)? {
+            (0, n) => Ok((None, n)),
+            (1, n) => {
+                let (value, m) = T::read_in(&buf[n..], options)?;
+                Ok((Some(value), n + m))
+            }
+            (tag, _) => Err(DecodeError::InvalidTag {
+                ty: "Option",
+                tag: tag.into(),
+            }),
+        }
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        match u8::read_from(buf)? {
+            (0, n) => Ok(n),
+            (1, n) => Ok(n + T::skip_in(&buf[n..], options)?),
+            (tag, _) => Err(DecodeError::InvalidTag {
+                ty: "Option",
+                tag: tag.into(),
+            }),
+        }
+    }
+}
+
+// `FIXED_SIZE` is left at `None` rather than taken from `T`: a derived type
+// that holds itself in a `Box` would otherwise compute its constant from
+// itself and fail to compile.
+impl<T: SizedOnDisk + ?Sized> SizedOnDisk for Box<T> {
+    fn size(&self) -> usize {
+        (**self).size()
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        (**self).size_in(options)
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        (**self).checked_size()
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        (**self).checked_size_in(options)
+    }
+}
+
+impl<T: ToDisk + ?Sized> ToDisk for Box<T> {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        (**self).write_to(out)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        (**self).write_in(out, options)
+    }
+}
+
+impl<T: FromDisk> FromDisk for Box<T> {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (value, n) = T::read_from(buf)?;
+        Ok((Box::new(value), n))
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let (value, n) = T::read_in(buf, options)?;
+        Ok((Box::new(value), n))
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        T::skip_in(buf, options)
+    }
+}
+
+impl FromDisk for Box<str> {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let (value, n) = String::read_in(buf, options)?;
+        Ok((value.into_boxed_str(), n))
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        String::skip_in(buf, options)
+    }
+}
+
+impl<T: SizedOnDisk, const N: usize> SizedOnDisk for [T; N] {
+    const FIXED_SIZE: Option<usize> = match T::FIXED_SIZE {
+        Some(size) => size.checked_mul(N),
+        None => None,
+    };
+
+    fn size(&self) -> usize {
+        match Self::FIXED_SIZE {
+            Some(size) => size,
+            None => self.iter().map(SizedOnDisk::size).sum(),
+        }
+    }
+
+    fn size_in(&self, options: Options) -> usize {
+        match Self::FIXED_SIZE {
+            Some(size) if options.fixed_width() => size,
+            _ => self.iter().map(|item| item.size_in(options)).sum(),
+        }
+    }
+
+    fn checked_size(&self) -> Option<usize> {
+        match Self::FIXED_SIZE {
+            Some(size) => Some(size),
+            None => checked_sum(0, self.iter().map(SizedOnDisk::checked_size)),
+        }
+    }
+
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        match Self::FIXED_SIZE {
+            Some(size) if options.fixed_width() => Some(size),
+            _ => checked_sum(0, self.iter().map(|item| item.checked_size_in(options))),
+        }
+    }
+}
+
+impl<T: ToDisk, const N: usize> ToDisk for [T; N] {
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.write_in(out, Options::DEFAULT)
+    }
+
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        let mut n = 0;
+        for item in self {
+            n += item.write_in(&mut out[n..], options)?;
+        }
+        Ok(n)
+    }
+}
+
+impl<T: FromDisk, const N: usize> FromDisk for [T; N] {
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::read_in(buf, Options::DEFAULT)
+    }
+
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let mut items = Vec::with_capacity(N);
+        let mut n = 0;
+        for _ in 0..N {
+            let (item, m) = T::read_in(&buf[n..], options)?;
+            items.push(item);
+            n += m;
+        }
+        match items.try_into() {
+            Ok(items) => Ok((items, n)),
+            Err(_) => unreachable!("read exactly N items"),
+        }
+    }
+
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        let mut n = 0;
+        for _ in 0..N {
+            n += T::skip_in(&buf[n..], options)?;
+        }
+        Ok(n)
+    }
+}
+
+macro_rules! tuple {
+    ($(($($name:ident $index:tt),+))*) => {
+        $(
+            impl<$($name: SizedOnDisk),+> SizedOnDisk for ($($name,)+) {
+                const FIXED_SIZE: Option<usize> = fixed_sum(&[$($name::FIXED_SIZE),+]);
+
+                fn size(&self) -> usize {
+                    match Self::FIXED_SIZE {
+                        Some(size) => size,
+                        None => 0 $(+ self.$index.size())+,
+                    }
+                }
+
+                fn size_in(&self, options: Options) -> usize {
+                    match Self::FIXED_SIZE {
+                        Some(size) if options.fixed_width() => size,
+                        _ => 0 $(+ self.$index.size_in(options))+,
+                    }
+                }
+
+                fn checked_size(&self) -> Option<usize> {
+                    match Self::FIXED_SIZE {
+                        Some(size) => Some(size),
+                        None => checked_sum(0, [$(self.$index.checked_size()),+]),
+                    }
+                }
+
+                fn checked_size_in(&self, options: Options) -> Option<usize> {
+                    match Self::FIXED_SIZE {
+                        Some(size) if options.fixed_width() => Some(size),
+                        _ => checked_sum(0, [$(self.$index.checked_size_in(options)),+]),
+                    }
+                }
+            }
+
+            impl<$($name: ToDisk),+> ToDisk for ($($name,)+) {
+                fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError> {
+                    self.write_in(out, Options::DEFAULT)
+                }
+
+                fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+                    let mut n = 0;
+                    $(n += self.$index.write_in(&mut out[n..], options)?;)+
+                    Ok(n)
+                }
+            }
+
+            impl<$($name: FromDisk),+> FromDisk for ($($name,)+) {
+                fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    Self::read_in(buf, Options::DEFAULT)
+                }
+
+                fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+                    let mut n = 0;
+                    let value = ($(
+                        {
+                            let (item, m) = $name::read_in(&buf[n..], options)?;
+                            n += m;
+                            item
+                        },
+                    )+);
+                    Ok((value, n))
+                }
+
+                fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+                    let mut n = 0;
+                    $(n += $name::skip_in(&buf[n..], options)?;)+
+                    Ok(n)
+                }
+            }
+        )*
+    };
+}
+
+tuple! {
+    (A 0)
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11)
+}
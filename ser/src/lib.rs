@@ -0,0 +1,369 @@
+//! Runtime support for the `ser_derive` derives.
+//!
+//! [`SizedOnDisk`] reports how many bytes a value occupies in its on-disk
+//! encoding, [`ToDisk`] writes that encoding and [`FromDisk`] reads it back.
+//! The derives of the same names implement them for structs and enums field
+//! by field, and this crate implements them for the primitive and standard
+//! library types those fields are built from.
+//!
+//! # Encoding
+//!
+//! Every value is laid out as follows, with no padding between values:
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `u8`..`u128`, `i8`..`i128` | fixed width, little-endian by default |
+//! | `usize`, `isize` | as `u64` / `i64`, so the layout does not depend on the host |
+//! | `f32`, `f64` | IEEE 754 bits, in the same byte order as integers |
+//! | `bool` | one byte, `0` or `1` |
+//! | `char` | the scalar value as a `u32` |
+//! | `str`, `String` | a `u32` byte length followed by the UTF-8 bytes |
+//! | `Vec<T>` | a `u32` element count followed by each element |
+//! | `Option<T>` | one byte, `0` for `None` or `1` followed by the value |
+//! | `Box<T>` | the boxed value |
+//! | `[T; N]` | each element, without a length |
+//! | tuples | each element in order; `()` takes no bytes |
+//! | `PhantomData<T>` | no bytes |
+//!
+//! Derived structs are their fields in declaration order, skipping fields
+//! marked `#[dignore]`. Derived enums are a tag followed by the fields of the
+//! active variant; see [`ser_derive::SizedOnDisk`] for how the tag is chosen.
+//!
+//! `#[disk(endian = "big" | "little" | "native")]` on a derived type or one
+//! of its fields changes the byte order of the numbers in it, including
+//! those inside std containers such as `Vec<u32>` and inside derived types
+//! that do not choose their own. The derives express this through
+//! [`Options`], which the `*_in` trait methods take.
+//!
+//! `#[disk(varint)]` and `#[disk(zigzag)]` on a field write its integers in
+//! the variable-length forms described by [`IntEncoding`], which `size`
+//! reports exactly. Such a field has no fixed size.
+//!
+//! `#[disk(len = "u8" | "u16" | "u32" | "varint")]` on a field, or on a
+//! derived type for all of its fields, sets the width of the length prefixes
+//! of the strings and collections in it (see [`LenPrefix`]). Writing a
+//! length the prefix cannot hold fails with [`EncodeError::LengthOverflow`].
+//!
+//! `#[disk(align = N)]` inserts zero padding before a field, or after the
+//! last one when placed on the type, so direct-I/O layouts can be described
+//! field by field. Decoding skips the padding without checking it:
+//!
+//! ```
+//! use ser::{DecodeError, FromDisk, SizedOnDisk, ToDisk};
+//!
+//! #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+//! #[disk(align = 8)]
+//! struct Slot {
+//!     tag: u8,
+//!     #[disk(align = 4)]
+//!     id: u32,
+//!     len: u16,
+//! }
+//!
+//! assert_eq!(Slot::FIXED_SIZE, Some(16));
+//! let slot = Slot { tag: 1, id: 2, len: 3 };
+//! let mut buf = [0xff; 16];
+//! assert_eq!(slot.write_to(&mut buf), Ok(16));
+//! assert_eq!(buf, [1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+//!
+//! buf[1..4].fill(0xff);
+//! buf[10..].fill(0xff);
+//! assert_eq!(Slot::read_from(&buf), Ok((slot, 16)));
+//! assert_eq!(Slot::read_from(&buf[..15]), Err(DecodeError::UnexpectedEof));
+//! ```
+//!
+//! `#[disk(version = N)]` with `#[disk(since = ..)]` and `#[disk(until = ..)]`
+//! on fields lets one type read and write every version of a record; see
+//! [`ser_derive::SizedOnDisk`]. Fields a version does not have are decoded
+//! as their defaults:
+//!
+//! ```
+//! use ser::{FromDisk, SizedOnDisk, ToDisk};
+//!
+//! fn default_level() -> u8 {
+//!     9
+//! }
+//!
+//! #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+//! #[disk(version = 2)]
+//! struct Record {
+//!     id: u32,
+//!     #[disk(since = 2, default = "default_level")]
+//!     level: u8,
+//!     #[disk(until = 2)]
+//!     legacy: u16,
+//! }
+//!
+//! let record = Record { id: 1, level: 2, legacy: 3 };
+//! assert_eq!(record.size_at_version(1), 6);
+//! assert_eq!(record.size(), 5);
+//!
+//! let mut buf = vec![0; record.size_at_version(1)];
+//! assert_eq!(record.write_to_version(&mut buf, 1), Ok(6));
+//! assert_eq!(buf, [1, 0, 0, 0, 3, 0]);
+//! let old = Record { id: 1, level: 9, legacy: 3 };
+//! assert_eq!(Record::read_from_version(&buf, 1), Ok((old, 6)));
+//!
+//! let mut buf = vec![0; record.size()];
+//! assert_eq!(record.write_to(&mut buf), Ok(5));
+//! assert_eq!(buf, [1, 0, 0, 0, 2]);
+//! let new = Record { id: 1, level: 2, legacy: 0 };
+//! assert_eq!(Record::read_from(&buf), Ok((new, 5)));
+//! ```
+//!
+//! `#[disk(checksum = "crc32c" | "xxh64")]` on a type appends the
+//! [`checksum`] of its encoded bytes, which decoding verifies.
+//!
+//! `#[disk(bits = N)]` on consecutive fields packs them into one integer,
+//! the first field in the lowest bits, which takes the smallest of `u8`,
+//! `u16`, `u32` and `u64` that holds them all. The field types implement
+//! [`Bits`]:
+//!
+//! ```
+//! use ser::{Bits, SizedOnDisk, ToDisk};
+//!
+//! #[derive(Clone, Copy, Bits)]
+//! enum Kind {
+//!     Leaf,
+//!     Inner,
+//! }
+//!
+//! #[derive(SizedOnDisk, ToDisk)]
+//! struct Header {
+//!     #[disk(bits = 1)]
+//!     dirty: bool,
+//!     #[disk(bits = 2)]
+//!     kind: Kind,
+//!     #[disk(bits = 5)]
+//!     level: u8,
+//!     count: u16,
+//! }
+//!
+//! assert_eq!(Header::FIXED_SIZE, Some(3));
+//! let mut buf = [0; 3];
+//! let header = Header { dirty: true, kind: Kind::Inner, level: 3, count: 1 };
+//! header.write_to(&mut buf).unwrap();
+//! assert_eq!(buf, [0b00011_01_1, 1, 0]);
+//! ```
+//!
+//! `#[disk(transparent)]` on a newtype such as `struct PageId(u64)` encodes it
+//! exactly as its one field, passing [`Options`] through, so it can be used
+//! wherever the field type could.
+//!
+//! The derived `schema()` function describes all of this as a [`Schema`], for
+//! documentation and compatibility checks.
+//!
+//! [`DiskView`] derives a borrowed view of a struct's encoding that decodes
+//! one field at a time, for scans that only need a few fields of a record.
+//! Strings and byte vectors are borrowed from the buffer rather than copied:
+//!
+//! ```
+//! use ser::{DiskView, SizedOnDisk, ToDisk};
+//!
+//! #[derive(SizedOnDisk, ToDisk, DiskView)]
+//! struct Row {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! let row = Row { id: 7, name: "abc".into() };
+//! let mut buf = vec![0; row.size()];
+//! row.write_to(&mut buf).unwrap();
+//!
+//! let view = RowRef::new(&buf).unwrap();
+//! assert_eq!(view.id(), 7);
+//! assert_eq!(view.name(), "abc");
+//! ```
+//!
+//! ```
+//! use ser::{FromDisk, SizedOnDisk, ToDisk};
+//!
+//! #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+//! struct Entry {
+//!     key: u64,
+//!     value: String,
+//!     #[dignore]
+//!     cached: bool,
+//! }
+//!
+//! let entry = Entry { key: 7, value: "abc".into(), cached: true };
+//! assert_eq!(entry.size(), 8 + 4 + 3);
+//!
+//! let mut buf = vec![0; entry.size()];
+//! assert_eq!(entry.write_to(&mut buf), Ok(buf.len()));
+//! assert_eq!(buf, [7, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, b'a', b'b', b'c']);
+//!
+//! let (decoded, n) = Entry::read_from(&buf).unwrap();
+//! assert_eq!(n, buf.len());
+//! assert_eq!(decoded, Entry { key: 7, value: "abc".into(), cached: false });
+//! ```
+
+// Lets the derives refer to this crate as `::ser` from inside it.
+extern crate self as ser;
+
+use std::io;
+
+mod bits;
+pub mod checksum;
+mod error;
+mod impls;
+mod options;
+pub mod schema;
+
+#[doc(hidden)]
+pub mod __private;
+
+pub use bits::Bits;
+pub use error::{DecodeError, EncodeError};
+pub use options::{Endian, IntEncoding, LenPrefix, Options};
+pub use schema::Schema;
+pub use ser_derive::{Bits, DiskView, FromDisk, SizedOnDisk, ToDisk};
+
+/// A value with a known on-disk encoding.
+///
+/// See the [crate documentation](crate#encoding) for the layout of the
+/// implementations provided here.
+pub trait SizedOnDisk {
+    /// The size every value of the type occupies, when that does not depend
+    /// on the value.
+    ///
+    /// Derived impls compute it at compile time from the constants of their
+    /// field types, so it can size buffers in const contexts:
+    ///
+    /// ```
+    /// use ser::SizedOnDisk;
+    ///
+    /// #[derive(SizedOnDisk)]
+    /// struct PageHeader {
+    ///     id: u64,
+    ///     flags: u16,
+    ///     checksum: [u8; 4],
+    /// }
+    ///
+    /// const HEADER_SIZE: usize = PageHeader::FIXED_SIZE.unwrap();
+    /// let buf = [0u8; HEADER_SIZE];
+    /// assert_eq!(buf.len(), 14);
+    /// ```
+    const FIXED_SIZE: Option<usize> = None;
+
+    /// The number of bytes `self` occupies when encoded.
+    fn size(&self) -> usize;
+
+    /// Like [`size`](SizedOnDisk::size), but for the encoding `options`
+    /// select.
+    ///
+    /// Types whose layout does not depend on options keep the provided
+    /// implementation. Derived types measure their fields with `options`,
+    /// except where the type or the field sets its own.
+    fn size_in(&self, options: Options) -> usize {
+        let _ = options;
+        self.size()
+    }
+
+    /// Like [`size`](SizedOnDisk::size), but `None` if the size does not fit
+    /// in a `usize`.
+    ///
+    /// Derived impls and the implementations for strings and std containers
+    /// add up their parts with `checked_add`, so a corrupt length or a huge
+    /// nested collection is reported here instead of panicking or wrapping
+    /// around in `size`:
+    ///
+    /// ```
+    /// use ser::SizedOnDisk;
+    ///
+    /// #[derive(SizedOnDisk)]
+    /// struct Record {
+    ///     id: u64,
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// let record = Record { id: 1, tags: vec!["a".into(), "bc".into()] };
+    /// assert_eq!(record.checked_size(), Some(8 + 4 + 5 + 6));
+    /// ```
+    ///
+    /// The provided implementation is `Some(self.size())`, for types whose
+    /// size cannot overflow.
+    fn checked_size(&self) -> Option<usize> {
+        Some(self.size())
+    }
+
+    /// Like [`checked_size`](SizedOnDisk::checked_size), but for the encoding
+    /// `options` select.
+    ///
+    /// Types whose layout does not depend on options keep the provided
+    /// implementation.
+    fn checked_size_in(&self, options: Options) -> Option<usize> {
+        let _ = options;
+        self.checked_size()
+    }
+}
+
+/// A value that can write its on-disk encoding.
+///
+/// Implementations write exactly [`size`](SizedOnDisk::size) bytes.
+pub trait ToDisk: SizedOnDisk {
+    /// Writes the encoding of `self` to the start of `out` and returns the
+    /// number of bytes written.
+    ///
+    /// Fails if a length does not fit its length prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than [`size`](SizedOnDisk::size).
+    fn write_to(&self, out: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Like [`write_to`](ToDisk::write_to), but encodes primitives as
+    /// `options` says.
+    ///
+    /// Types whose layout does not depend on options keep the provided
+    /// implementation. Derived types write their fields with `options`,
+    /// except where the type or the field sets its own.
+    fn write_in(&self, out: &mut [u8], options: Options) -> Result<usize, EncodeError> {
+        let _ = options;
+        self.write_to(out)
+    }
+
+    /// Writes the encoding of `self` to `writer` and returns the number of
+    /// bytes written.
+    ///
+    /// An [`EncodeError`] is reported as [`io::ErrorKind::InvalidInput`].
+    fn write_io<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let mut buf = vec![0; self.size()];
+        let n = self.write_to(&mut buf)?;
+        writer.write_all(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+/// A value that can be read back from its on-disk encoding.
+pub trait FromDisk: Sized {
+    /// Reads a value from the start of `buf` and returns it together with the
+    /// number of bytes it occupied.
+    fn read_from(buf: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Like [`read_from`](FromDisk::read_from), but decodes primitives as
+    /// `options` says.
+    ///
+    /// Types whose layout does not depend on options keep the provided
+    /// implementation. Derived types read their fields with `options`,
+    /// except where the type or the field sets its own.
+    fn read_in(buf: &[u8], options: Options) -> Result<(Self, usize), DecodeError> {
+        let _ = options;
+        Self::read_from(buf)
+    }
+
+    /// Checks that `buf` starts with a valid encoding and returns the number
+    /// of bytes it occupies, without keeping the value.
+    fn skip(buf: &[u8]) -> Result<usize, DecodeError> {
+        Self::skip_in(buf, Options::DEFAULT)
+    }
+
+    /// Like [`skip`](FromDisk::skip), but for the encoding `options` select.
+    ///
+    /// The provided implementation decodes the value and drops it. The
+    /// implementations for strings and std containers check the bytes in
+    /// place instead, so skipping them does not allocate.
+    fn skip_in(buf: &[u8], options: Options) -> Result<usize, DecodeError> {
+        Self::read_in(buf, options).map(|(_, n)| n)
+    }
+}
@@ -0,0 +1,127 @@
+/// The byte order of multi-byte numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first, as in network protocols.
+    Big,
+    /// The byte order of the machine running the code.
+    Native,
+}
+
+/// How integers are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IntEncoding {
+    /// The full width of the type, in the byte order of the options.
+    #[default]
+    Fixed,
+    /// LEB128: seven bits per byte, least significant first, with the high
+    /// bit set on every byte but the last. Signed integers are written as
+    /// the unsigned integer of the same width with the same bits, so small
+    /// negative numbers take the most bytes.
+    Varint,
+    /// Signed integers mapped to unsigned ones as `0, -1, 1, -2, ...` and
+    /// then written as [`Varint`](IntEncoding::Varint), so small negative
+    /// numbers stay short. Unsigned integers are written as `Varint`.
+    Zigzag,
+}
+
+/// The integer written before the contents of a string or collection to
+/// give its length.
+///
+/// `#[disk(len = "...")]` picks one per field. Writing a length the prefix
+/// cannot hold fails:
+///
+/// ```
+/// use ser::{EncodeError, FromDisk, SizedOnDisk, ToDisk};
+///
+/// #[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+/// struct Names {
+///     #[disk(len = "u8")]
+///     short: String,
+///     #[disk(len = "varint")]
+///     long: Vec<u16>,
+/// }
+///
+/// let names = Names { short: "a".repeat(255), long: vec![7; 128] };
+/// assert_eq!(names.size(), 1 + 255 + 2 + 256);
+/// let mut buf = vec![0; names.size()];
+/// assert_eq!(names.write_to(&mut buf), Ok(514));
+/// assert_eq!(buf[256..258], [0x80, 0x01]);
+/// assert_eq!(Names::read_from(&buf), Ok((names, 514)));
+///
+/// let names = Names { short: "a".repeat(256), long: vec![] };
+/// let mut buf = vec![0; names.size()];
+/// assert_eq!(
+///     names.write_to(&mut buf),
+///     Err(EncodeError::LengthOverflow { len: 256, max: 255 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LenPrefix {
+    /// One byte, for lengths up to 255.
+    U8,
+    /// Two bytes, for lengths up to 65535.
+    U16,
+    /// Four bytes.
+    #[default]
+    U32,
+    /// LEB128, as in [`IntEncoding::Varint`].
+    Varint,
+}
+
+/// Choices that change how primitive values are encoded.
+///
+/// The derives pass each field the options they are given, with whatever the
+/// attributes of the type and the field select in their place, and the
+/// implementations for std containers hand them on to their elements. A
+/// derived type nested in another one therefore follows its parent's options
+/// where it sets none of its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Options {
+    /// The byte order of numbers, including length prefixes and enum tags.
+    pub endian: Endian,
+    /// The encoding of integers. Length prefixes, enum tags, floats and
+    /// `char`s are always fixed width.
+    pub int: IntEncoding,
+    /// The length prefix of strings and collections.
+    pub len: LenPrefix,
+}
+
+impl Options {
+    /// The encoding described in the [crate documentation](crate#encoding).
+    pub const DEFAULT: Options = Options {
+        endian: Endian::Little,
+        int: IntEncoding::Fixed,
+        len: LenPrefix::U32,
+    };
+
+    /// These options with numbers in the given byte order.
+    pub const fn with_endian(self, endian: Endian) -> Options {
+        Options { endian, ..self }
+    }
+
+    /// These options with integers in the given encoding.
+    pub const fn with_int(self, int: IntEncoding) -> Options {
+        Options { int, ..self }
+    }
+
+    /// These options with length prefixes of the given width.
+    pub const fn with_len(self, len: LenPrefix) -> Options {
+        Options { len, ..self }
+    }
+
+    // Whether values take as many bytes as with the default options, so
+    // `FIXED_SIZE` still holds. Types with a fixed size have no length
+    // prefixes, so only the integer encoding matters.
+    pub(crate) fn fixed_width(self) -> bool {
+        self.int == IntEncoding::Fixed
+    }
+
+    // The options for numbers that are always fixed width.
+    pub(crate) fn fixed(self) -> Options {
+        Options::DEFAULT.with_endian(self.endian)
+    }
+}
@@ -0,0 +1,100 @@
+//! Descriptions of derived encodings, for documentation and compatibility
+//! checks.
+//!
+//! `#[derive(SizedOnDisk)]` adds a `schema()` function to the type that
+//! returns its [`Schema`]:
+//!
+//! ```
+//! use ser::schema::Layout;
+//! use ser::SizedOnDisk;
+//!
+//! #[derive(SizedOnDisk)]
+//! struct Entry {
+//!     key: u64,
+//!     #[disk(varint)]
+//!     len: u32,
+//! }
+//!
+//! let schema = Entry::schema();
+//! assert_eq!(schema.name, "Entry");
+//! assert_eq!(schema.fixed_size, None);
+//! let Layout::Struct(fields) = schema.layout else { unreachable!() };
+//! assert_eq!((fields[0].name, fields[0].ty), ("key", "u64"));
+//! assert_eq!(fields[0].fixed_size, Some(8));
+//! ```
+
+use crate::Options;
+
+/// The encoding of a type, as its derives lay it out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schema {
+    /// The name of the type, without its generics.
+    pub name: &'static str,
+    /// The fields, or the variants of an enum.
+    pub layout: Layout,
+    /// The type's `SizedOnDisk::FIXED_SIZE`.
+    pub fixed_size: Option<usize>,
+    /// The `#[disk(version = N)]` the type is encoded in.
+    pub version: Option<u32>,
+    /// The `#[disk(checksum = "...")]` appended to the encoding.
+    pub checksum: Option<&'static str>,
+    /// The `#[disk(align = N)]` the encoding is padded to.
+    pub align: Option<usize>,
+    /// Marked `#[disk(transparent)]`: encoded exactly as its one encoded
+    /// field, whose entry in `layout` describes it.
+    pub transparent: bool,
+}
+
+/// The body of a [`Schema`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// A struct and its fields, in declaration order.
+    Struct(&'static [Field]),
+    /// An enum, which writes a tag of type `tag` before the fields of the
+    /// active variant.
+    Enum {
+        /// The integer type of the tag.
+        tag: &'static str,
+        /// The variants, in declaration order.
+        variants: &'static [Variant],
+    },
+}
+
+/// A variant of an enum [`Layout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Variant {
+    /// The name of the variant.
+    pub name: &'static str,
+    /// The tag written for the variant.
+    pub tag: i128,
+    /// The fields of the variant, in declaration order.
+    pub fields: &'static [Field],
+}
+
+/// A field of a struct or enum variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field {
+    /// The name of the field, or its position in a tuple struct or variant.
+    pub name: &'static str,
+    /// The type of the field as written in the source.
+    pub ty: &'static str,
+    /// Marked `#[dignore]`: not part of the encoding.
+    pub ignored: bool,
+    /// The byte order, integer encoding and length prefix of the field, with
+    /// those of the type filled in.
+    pub options: Options,
+    /// The `#[disk(align = N)]` of the field.
+    pub align: Option<usize>,
+    /// The `#[disk(since = N)]` of the field.
+    pub since: Option<u32>,
+    /// The `#[disk(until = N)]` of the field.
+    pub until: Option<u32>,
+    /// The `#[disk(bits = N)]` of the field.
+    pub bits: Option<u32>,
+    /// Encoded by `size_with`, `encode_with` or `decode_with` functions
+    /// rather than the field type's impls.
+    pub custom: bool,
+    /// The number of bytes the field always takes, `0` for an ignored field,
+    /// or the size of the whole group for a `bits` field.
+    pub fixed_size: Option<usize>,
+}
@@ -0,0 +1,9 @@
+// Checks what the derives accept and the diagnostics for what they reject.
+// Run with `TRYBUILD=overwrite` to regenerate the expected `.stderr` files
+// after changing a message.
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
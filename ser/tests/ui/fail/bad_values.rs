@@ -0,0 +1,24 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[disk(endian = "middle")]
+#[disk(checksum = "md5")]
+#[disk(align = 3)]
+#[disk(version = -1)]
+#[disk(len = "u8", len = "u16")]
+struct Values {
+    #[disk(len = "u64")]
+    a: String,
+    #[disk(bits = 65)]
+    b: u64,
+    #[disk(endian = "big", endian = "little")]
+    c: u32,
+    #[disk(align = 8, align = 8)]
+    d: u32,
+    #[disk(bound = "T")]
+    e: u32,
+    #[disk(size_with)]
+    f: u32,
+}
+
+fn main() {}
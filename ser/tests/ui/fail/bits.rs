@@ -0,0 +1,24 @@
+use ser::SizedOnDisk;
+
+fn size(_: &u8) -> usize {
+    1
+}
+
+#[derive(SizedOnDisk)]
+#[disk(version = 1)]
+struct Packed {
+    #[disk(bits = 0)]
+    a: u8,
+    #[disk(bits = 4, varint)]
+    b: u8,
+    #[disk(bits = 4, size_with = size, endian = "big", len = "u8", align = 2, since = 1)]
+    c: u8,
+    #[disk(bits = 2, bits = 2)]
+    d: u8,
+    #[disk(bits = 60)]
+    e: u64,
+    #[disk(bits = 8)]
+    f: u8,
+}
+
+fn main() {}
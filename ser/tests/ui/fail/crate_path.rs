@@ -0,0 +1,15 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[sized_on_disk(krate = "ser")]
+struct Unknown(u8);
+
+#[derive(SizedOnDisk)]
+#[sized_on_disk(crate = "ser", crate = "ser")]
+struct Duplicate(u8);
+
+#[derive(SizedOnDisk)]
+#[sized_on_disk(crate = "ser::")]
+struct Malformed(u8);
+
+fn main() {}
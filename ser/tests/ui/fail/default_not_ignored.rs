@@ -0,0 +1,16 @@
+use ser::SizedOnDisk;
+
+fn zero() -> u8 {
+    0
+}
+
+#[derive(SizedOnDisk)]
+struct Record {
+    #[disk(default = "zero")]
+    level: u8,
+    #[dignore]
+    #[disk(default = "")]
+    empty: u8,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use ser::Bits;
+
+#[derive(Bits)]
+struct Flag(bool);
+
+#[derive(Bits)]
+enum Value {
+    Small(u8),
+    None,
+}
+
+fn main() {}
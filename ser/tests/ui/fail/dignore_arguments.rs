@@ -0,0 +1,26 @@
+use ser::SizedOnDisk;
+
+fn size(_: &u8) -> usize {
+    1
+}
+
+#[derive(SizedOnDisk)]
+struct Ignored {
+    #[dignore]
+    #[disk(size_with = size)]
+    #[disk(encode_with = size)]
+    #[disk(decode_with = size)]
+    #[disk(endian = "big")]
+    #[disk(len = "u8")]
+    #[disk(align = 4)]
+    a: u8,
+    #[dignore]
+    #[disk(varint)]
+    #[disk(zigzag)]
+    #[disk(since = 1)]
+    #[disk(until = 2)]
+    #[disk(bits = 2)]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use ser::DiskView;
+
+#[derive(DiskView)]
+enum Either {
+    A(u8),
+    B(u16),
+}
+
+#[derive(DiskView)]
+struct Reserved {
+    new: u8,
+    as_bytes: u8,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+struct Record {
+    name: String,
+    flags: u8,
+}
+
+fn main() {
+    let _ = Record::OFFSET_NAME;
+    let _ = Record::OFFSET_FLAGS;
+}
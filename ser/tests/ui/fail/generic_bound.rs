@@ -0,0 +1,15 @@
+use ser::SizedOnDisk;
+
+struct Opaque;
+
+// `T` is bounded by `SizedOnDisk` because `items` mentions it.
+#[derive(SizedOnDisk)]
+struct Wrapper<T> {
+    items: Vec<T>,
+}
+
+fn assert_sized<T: SizedOnDisk>() {}
+
+fn main() {
+    assert_sized::<Wrapper<Opaque>>();
+}
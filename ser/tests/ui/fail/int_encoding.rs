@@ -0,0 +1,13 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+struct Ints {
+    #[disk(varint, zigzag)]
+    a: i32,
+    #[disk(varint, varint)]
+    b: u32,
+    #[disk(varint = true)]
+    c: u32,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[dignore]
+enum Shape {
+    #[dignore]
+    #[disk(len = "u8")]
+    #[sized_on_disk(crate = "ser")]
+    Circle(u32),
+    Square {
+        #[sized_on_disk(crate = "ser")]
+        side: u32,
+        #[dignore(always)]
+        cache: u32,
+    },
+}
+
+fn main() {}
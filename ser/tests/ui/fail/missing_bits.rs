@@ -0,0 +1,9 @@
+use ser::{SizedOnDisk, ToDisk};
+
+#[derive(SizedOnDisk, ToDisk)]
+struct Record {
+    #[disk(bits = 4)]
+    name: String,
+}
+
+fn main() {}
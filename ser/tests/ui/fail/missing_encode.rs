@@ -0,0 +1,12 @@
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(SizedOnDisk)]
+struct Opaque;
+
+#[derive(SizedOnDisk, ToDisk, FromDisk)]
+struct Record {
+    id: u64,
+    payload: Opaque,
+}
+
+fn main() {}
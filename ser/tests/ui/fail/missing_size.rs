@@ -0,0 +1,20 @@
+use ser::SizedOnDisk;
+
+struct Opaque;
+
+#[derive(SizedOnDisk)]
+struct Record {
+    id: u64,
+    payload: Opaque,
+}
+
+#[derive(SizedOnDisk)]
+struct Tuple(u8, Opaque);
+
+#[derive(SizedOnDisk)]
+enum Message {
+    Empty,
+    Data { len: u32, payload: Opaque },
+}
+
+fn main() {}
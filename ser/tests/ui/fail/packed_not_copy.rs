@@ -0,0 +1,11 @@
+use ser::SizedOnDisk;
+
+// The fields of a packed struct are copied out rather than borrowed.
+#[derive(SizedOnDisk)]
+#[repr(packed)]
+struct Header {
+    magic: u8,
+    name: String,
+}
+
+fn main() {}
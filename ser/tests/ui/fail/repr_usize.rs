@@ -0,0 +1,10 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[repr(usize)]
+enum Wide {
+    A,
+    B,
+}
+
+fn main() {}
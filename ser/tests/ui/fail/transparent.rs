@@ -0,0 +1,36 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[disk(transparent)]
+struct Pair(u8, u16);
+
+#[derive(SizedOnDisk)]
+#[disk(transparent)]
+struct Nothing {
+    #[dignore]
+    cached: u8,
+}
+
+#[derive(SizedOnDisk)]
+#[disk(transparent)]
+enum Choice {
+    A(u8),
+}
+
+#[derive(SizedOnDisk)]
+#[disk(transparent, align = 8, version = 1)]
+struct Aligned(u64);
+
+#[derive(SizedOnDisk)]
+#[disk(transparent)]
+struct Encoded(#[disk(varint, endian = "big")] u64);
+
+#[derive(SizedOnDisk)]
+#[disk(transparent = true)]
+struct Value(u64);
+
+#[derive(SizedOnDisk)]
+#[disk(transparent, transparent)]
+struct Twice(u64);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+union Bytes {
+    word: u32,
+    byte: u8,
+}
+
+fn main() {}
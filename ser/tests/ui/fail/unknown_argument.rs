@@ -0,0 +1,10 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+#[disk(compress)]
+struct Record {
+    #[disk(skip)]
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use ser::SizedOnDisk;
+
+// A field whose size varies whatever its type ends the constants, so the
+// fields after it have none even when their size is fixed.
+#[derive(SizedOnDisk)]
+struct Record {
+    id: u32,
+    #[disk(varint)]
+    count: u64,
+    flags: u8,
+}
+
+fn main() {
+    let _ = Record::OFFSET_COUNT;
+    let _ = Record::SIZE_COUNT;
+    let _ = Record::OFFSET_FLAGS;
+    let _ = Record::SIZE_FLAGS;
+}
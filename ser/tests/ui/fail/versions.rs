@@ -0,0 +1,20 @@
+use ser::SizedOnDisk;
+
+#[derive(SizedOnDisk)]
+struct Unversioned {
+    #[disk(since = 2)]
+    a: u8,
+}
+
+#[derive(SizedOnDisk)]
+#[disk(version = 2)]
+struct Versioned {
+    #[disk(since = 3)]
+    a: u8,
+    #[disk(since = 2, until = 2)]
+    b: u8,
+    #[disk(until = 4294967296)]
+    c: u8,
+}
+
+fn main() {}
@@ -0,0 +1,73 @@
+use ser::{Bits, DiskView, FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Clone, Copy, Bits)]
+enum Kind {
+    Leaf,
+    Inner,
+}
+
+fn size_text(text: &&str) -> usize {
+    text.len()
+}
+
+fn encode_text(text: &&str, out: &mut [u8]) -> Result<usize, ser::EncodeError> {
+    out[..text.len()].copy_from_slice(text.as_bytes());
+    Ok(text.len())
+}
+
+fn no_text(_: &Option<u8>) -> usize {
+    0
+}
+
+fn default_level() -> u8 {
+    3
+}
+
+#[derive(SizedOnDisk, ToDisk, FromDisk, DiskView)]
+#[disk(endian = "big", len = "u16", align = 8, version = 3, checksum = "crc32c")]
+struct Record {
+    #[disk(endian = "little")]
+    id: u64,
+    #[disk(varint)]
+    count: u32,
+    #[disk(zigzag)]
+    delta: i64,
+    #[disk(len = "varint")]
+    name: String,
+    #[disk(align = 4)]
+    flags: u16,
+    #[disk(since = 2, until = 4, default = "default_level")]
+    level: u8,
+    #[disk(bits = 1)]
+    dirty: bool,
+    #[disk(bits = 1)]
+    kind: Kind,
+    #[dignore]
+    #[disk(default = "default_level", bound = "")]
+    scratch: u8,
+}
+
+#[derive(SizedOnDisk, ToDisk)]
+struct Custom<'a> {
+    #[disk(size_with = "size_text", encode_with = encode_text)]
+    text: &'a str,
+    #[disk(size_with = no_text)]
+    skipped: Option<u8>,
+}
+
+#[derive(SizedOnDisk)]
+#[sized_on_disk(crate = "ser")]
+struct Renamed(u8);
+
+fn main() {
+    assert_eq!(Record::VERSION, 3);
+    assert_eq!(Renamed::FIXED_SIZE, Some(1));
+    let custom = Custom {
+        text: "abc",
+        skipped: None,
+    };
+    assert_eq!(custom.size(), 3);
+    assert_eq!(custom.checked_size(), Some(3));
+    let schema = Record::schema();
+    assert_eq!(schema.version, Some(3));
+}
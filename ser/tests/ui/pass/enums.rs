@@ -0,0 +1,36 @@
+use ser::{Bits, FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+enum Message {
+    Ping,
+    Data(#[dignore] u8, Vec<u8>),
+    Move { x: i32, y: i32 },
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[repr(u16)]
+enum Explicit {
+    A = 10,
+    B,
+    C = 20,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Bits)]
+enum Mode {
+    Read,
+    Write,
+}
+
+#[derive(SizedOnDisk, ToDisk, FromDisk)]
+enum Never {}
+
+fn main() {
+    assert_eq!(Message::Ping.size(), 1);
+    assert_eq!(Message::Move { x: 1, y: 2 }.size(), 9);
+    assert_eq!(Explicit::FIXED_SIZE, Some(2));
+    let mut buf = [0; 2];
+    Explicit::B.write_to(&mut buf).unwrap();
+    assert_eq!(buf, [11, 0]);
+    assert_eq!(Mode::from_bits(1, 1), Ok(Mode::Write));
+    assert_eq!(Never::FIXED_SIZE, None);
+}
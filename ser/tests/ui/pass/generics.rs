@@ -0,0 +1,47 @@
+use std::marker::PhantomData;
+
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+// `T` is bounded by the derives; `M` only appears in `PhantomData` and is not.
+#[derive(SizedOnDisk, ToDisk, FromDisk)]
+struct Wrapper<'a, T, M>
+where
+    T: Clone,
+{
+    items: Vec<T>,
+    #[dignore]
+    label: Option<&'a str>,
+    marker: PhantomData<M>,
+}
+
+// The associated type is bounded instead of `I`.
+#[derive(SizedOnDisk, ToDisk)]
+struct Assoc<I: Iterator> {
+    first: I::Item,
+}
+
+#[derive(SizedOnDisk, ToDisk, FromDisk)]
+#[disk(bound = "T: ser::SizedOnDisk + ser::ToDisk + ser::FromDisk")]
+struct Explicit<T> {
+    value: Box<T>,
+}
+
+#[derive(SizedOnDisk, ToDisk, FromDisk)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+struct NotDisk;
+
+fn main() {
+    let wrapper: Wrapper<u32, NotDisk> = Wrapper {
+        items: vec![1, 2],
+        label: None,
+        marker: PhantomData,
+    };
+    assert_eq!(wrapper.size(), 4 + 8);
+    assert_eq!(Assoc::<std::vec::IntoIter<u8>> { first: 1 }.size(), 1);
+    assert_eq!(Explicit { value: Box::new(7u16) }.size(), 2);
+    assert_eq!(Either::<u8, u64>::Right(1).size(), 9);
+}
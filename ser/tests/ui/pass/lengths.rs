@@ -0,0 +1,49 @@
+use std::marker::PhantomData;
+
+use ser::{DecodeError, FromDisk, IntEncoding, LenPrefix, Options, SizedOnDisk, ToDisk};
+
+#[derive(Debug, Clone, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Marker;
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Markers {
+    tag: u8,
+    markers: Vec<Marker>,
+    phantoms: Vec<PhantomData<u64>>,
+}
+
+fn main() {
+    // A length too large to add to the offset of the bytes after it.
+    let varint = Options::DEFAULT.with_len(LenPrefix::Varint);
+    let mut buf = vec![0xff; 9];
+    buf.push(0x01);
+    assert_eq!(
+        String::read_in(&buf, varint),
+        Err(DecodeError::UnexpectedEof)
+    );
+    assert_eq!(
+        String::skip_in(&buf, varint),
+        Err(DecodeError::UnexpectedEof)
+    );
+
+    // Elements that take no bytes can outnumber the bytes after them, and
+    // skipping them does not loop over the count.
+    assert_eq!(Vec::<()>::skip_in(&buf, varint), Ok(10));
+    assert_eq!(Vec::<()>::read_from(&[2, 0, 0, 0]), Ok((vec![(), ()], 4)));
+    let markers = Markers {
+        tag: 1,
+        markers: vec![Marker; 3],
+        phantoms: vec![PhantomData; 2],
+    };
+    let mut buf = vec![0; markers.size()];
+    assert_eq!(markers.write_to(&mut buf), Ok(9));
+    assert_eq!(buf, [1, 3, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(Markers::skip(&buf), Ok(9));
+    assert_eq!(Markers::read_from(&buf), Ok((markers, 9)));
+
+    let varint = Options::DEFAULT.with_int(IntEncoding::Varint);
+    assert_eq!(
+        Vec::<u8>::skip_in(&[3, 0, 0, 0, 1, 2], varint),
+        Err(DecodeError::UnexpectedEof)
+    );
+}
@@ -0,0 +1,64 @@
+use ser::{Endian, FromDisk, Options, SizedOnDisk, ToDisk};
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Extent {
+    start: u32,
+    lens: Vec<u16>,
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[disk(endian = "little")]
+struct Pinned(u16);
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct File {
+    #[disk(endian = "big", len = "u8")]
+    extent: Extent,
+    #[disk(endian = "big")]
+    pinned: Pinned,
+    #[disk(varint)]
+    plain: Extent,
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[disk(endian = "big")]
+enum Entry {
+    File(Extent),
+    Empty,
+}
+
+fn main() {
+    let file = File {
+        extent: Extent {
+            start: 1,
+            lens: vec![2],
+        },
+        pinned: Pinned(3),
+        plain: Extent {
+            start: 300,
+            lens: vec![],
+        },
+    };
+    let mut buf = vec![0; file.size()];
+    assert_eq!(file.write_to(&mut buf), Ok(15));
+    assert_eq!(buf[..9], [0, 0, 0, 1, 1, 0, 2, 3, 0]);
+    assert_eq!(buf[9..], [0xac, 0x02, 0, 0, 0, 0]);
+    assert_eq!(File::read_from(&buf), Ok((file, 15)));
+
+    // The enum's byte order reaches its tag and the fields of `Extent`.
+    let entry = Entry::File(Extent {
+        start: 1,
+        lens: vec![],
+    });
+    let mut buf = vec![0; entry.size()];
+    assert_eq!(entry.write_to(&mut buf), Ok(9));
+    assert_eq!(buf, [0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    assert_eq!(Entry::read_from(&buf), Ok((entry, 9)));
+
+    let big = Options::DEFAULT.with_endian(Endian::Big);
+    let pinned = Pinned(1);
+    let mut buf = [0; 2];
+    assert_eq!(pinned.write_in(&mut buf, big), Ok(2));
+    assert_eq!(buf, [1, 0]);
+    assert_eq!(Pinned::read_in(&buf, big), Ok((pinned, 2)));
+}
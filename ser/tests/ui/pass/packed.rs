@@ -0,0 +1,29 @@
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Debug, Clone, Copy, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[repr(C, packed)]
+struct Header {
+    magic: u8,
+    len: u32,
+    #[disk(varint)]
+    id: u64,
+    #[dignore]
+    cached: Option<u16>,
+}
+
+#[derive(SizedOnDisk, ToDisk)]
+#[repr(packed(2))]
+struct Pair(u8, u64);
+
+fn main() {
+    let header = Header {
+        magic: 1,
+        len: 2,
+        id: 300,
+        cached: None,
+    };
+    let mut buf = vec![0; header.size()];
+    assert_eq!(header.write_to(&mut buf), Ok(7));
+    assert_eq!(Header::read_from(&buf), Ok((header, 7)));
+    assert_eq!(Pair(1, 2).size(), 9);
+}
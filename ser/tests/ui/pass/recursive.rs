@@ -0,0 +1,34 @@
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+enum Tree {
+    Leaf(u32),
+    Node(Box<Tree>, Box<Tree>),
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct List {
+    value: u8,
+    next: Option<Box<List>>,
+}
+
+fn main() {
+    assert_eq!(Tree::FIXED_SIZE, None);
+    let tree = Tree::Node(Box::new(Tree::Leaf(1)), Box::new(Tree::Leaf(2)));
+    let mut buf = vec![0; tree.size()];
+    assert_eq!(tree.write_to(&mut buf), Ok(11));
+    assert_eq!(buf, [1, 0, 1, 0, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(Tree::read_from(&buf), Ok((tree, 11)));
+
+    let list = List {
+        value: 1,
+        next: Some(Box::new(List {
+            value: 2,
+            next: None,
+        })),
+    };
+    let mut buf = vec![0; list.size()];
+    assert_eq!(list.write_to(&mut buf), Ok(4));
+    assert_eq!(buf, [1, 1, 2, 0]);
+    assert_eq!(List::read_from(&buf), Ok((list, 4)));
+}
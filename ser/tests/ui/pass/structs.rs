@@ -0,0 +1,40 @@
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Named {
+    id: u64,
+    name: String,
+    flags: u8,
+    #[dignore]
+    cached: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Tuple(u16, #[dignore] bool, [u8; 3]);
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Unit;
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Empty {}
+
+fn main() {
+    let named = Named {
+        id: 1,
+        name: "ab".into(),
+        flags: 0,
+        cached: Some(3),
+    };
+    assert_eq!(named.size(), 8 + 4 + 2 + 1);
+    assert_eq!(Named::OFFSET_NAME, 8);
+    let mut buf = vec![0; named.size()];
+    assert_eq!(named.write_to(&mut buf), Ok(buf.len()));
+    let (decoded, _) = Named::read_from(&buf).unwrap();
+    assert_eq!(decoded.cached, None);
+
+    assert_eq!(Tuple::FIXED_SIZE, Some(5));
+    assert_eq!((Tuple::OFFSET_0, Tuple::OFFSET_2), (0, 2));
+    assert_eq!(Unit::FIXED_SIZE, Some(0));
+    assert_eq!(Unit.size(), 0);
+    assert_eq!(Empty::read_from(&[]), Ok((Empty {}, 0)));
+}
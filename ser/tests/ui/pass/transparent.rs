@@ -0,0 +1,41 @@
+use ser::{FromDisk, SizedOnDisk, ToDisk};
+
+#[derive(Debug, Clone, Copy, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[disk(transparent)]
+struct PageId(u64);
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+#[disk(transparent)]
+struct Name {
+    #[dignore]
+    cached: Option<u32>,
+    text: String,
+}
+
+#[derive(Debug, PartialEq, SizedOnDisk, ToDisk, FromDisk)]
+struct Page {
+    #[disk(endian = "big")]
+    id: PageId,
+    #[disk(len = "u8")]
+    name: Name,
+    #[disk(varint)]
+    children: Vec<PageId>,
+}
+
+fn main() {
+    assert_eq!(PageId::FIXED_SIZE, Some(8));
+    assert!(PageId::schema().transparent);
+    let page = Page {
+        id: PageId(1),
+        name: Name {
+            cached: None,
+            text: "ab".into(),
+        },
+        children: vec![PageId(300)],
+    };
+    let mut buf = vec![0; page.size()];
+    assert_eq!(page.write_to(&mut buf), Ok(17));
+    assert_eq!(buf[..12], [0, 0, 0, 0, 0, 0, 0, 1, 2, b'a', b'b', 1]);
+    assert_eq!(buf[15..], [0xac, 0x02]);
+    assert_eq!(Page::read_from(&buf), Ok((page, 17)));
+}
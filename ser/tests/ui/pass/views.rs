@@ -0,0 +1,49 @@
+use std::marker::PhantomData;
+
+use ser::{DiskView, SizedOnDisk, ToDisk};
+
+#[derive(SizedOnDisk, ToDisk, DiskView)]
+#[disk(len = "u8")]
+struct Row {
+    id: u32,
+    name: String,
+    data: Vec<u8>,
+    #[disk(varint)]
+    counts: Vec<u8>,
+    lens: Vec<u16>,
+}
+
+#[derive(SizedOnDisk, ToDisk, DiskView)]
+struct Pair<'a>(u8, String, #[dignore] PhantomData<&'a ()>);
+
+// The borrowed accessors outlive the view they come from.
+fn name(buf: &[u8]) -> &str {
+    RowRef::new(buf).unwrap().name()
+}
+
+fn main() {
+    let row = Row {
+        id: 1,
+        name: "abc".into(),
+        data: vec![2, 3],
+        counts: vec![200],
+        lens: vec![4],
+    };
+    let mut buf = vec![0; row.size()];
+    assert_eq!(row.write_to(&mut buf), Ok(17));
+
+    let view = RowRef::new(&buf).unwrap();
+    assert_eq!(view.id(), 1);
+    let data: &[u8] = view.data();
+    assert_eq!(data, [2, 3]);
+    let counts: Vec<u8> = view.counts();
+    assert_eq!(counts, [200]);
+    assert_eq!(view.lens(), [4]);
+    assert_eq!(name(&buf), "abc");
+
+    let pair = Pair(1, "yz".into(), PhantomData);
+    let mut buf = vec![0; pair.size()];
+    pair.write_to(&mut buf).unwrap();
+    let view = PairRef::new(&buf).unwrap();
+    assert_eq!(view._1(), "yz");
+}
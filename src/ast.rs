@@ -0,0 +1,1044 @@
+//! The parsed form of a derive input that every derive in this crate expands
+//! from, so they all agree on which fields are encoded and in what order.
+
+use proc_macro2::{Literal, Span, TokenStream};
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::{format_ident, quote, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields, Generics, Ident, Index, LitInt,
+    LitStr, Member, Path, Token, Type, WherePredicate,
+};
+
+use crate::Errors;
+
+const IGNORE: &str = "dignore";
+const CONTAINER: &str = "sized_on_disk";
+const DISK: &str = "disk";
+
+// The runtime crate the generated code refers to by default.
+const RUNTIME_CRATE: &str = "ser";
+
+pub struct Container<'a> {
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    // The path of the crate or module that defines the runtime traits.
+    pub krate: Path,
+    // `#[disk(bound = "...")]`: replaces every inferred bound.
+    pub bound: Option<Vec<WherePredicate>>,
+    // `#[disk(endian = "...")]`: the byte order of every field that does not
+    // choose its own, and of the enum tag.
+    pub endian: Option<Endian>,
+    // `#[disk(len = "...")]`: the length prefix of every field that does not
+    // choose its own.
+    pub len: Option<LenPrefix>,
+    // `#[disk(align = N)]`: the encoded size is padded to a multiple of `N`.
+    pub align: Option<usize>,
+    // `#[disk(version = N)]`: the version the trait methods encode.
+    pub version: Option<u32>,
+    // `#[disk(checksum = "...")]`: appended after the fields.
+    pub checksum: Option<Checksum>,
+    // `#[repr(packed)]` or `#[repr(packed(N))]`: fields may be unaligned and
+    // cannot be borrowed.
+    pub repr_packed: bool,
+    // `#[disk(transparent)]`: a struct encoded exactly as its one encoded
+    // field, options included.
+    pub transparent: bool,
+    pub body: Body<'a>,
+}
+
+pub enum Body<'a> {
+    Struct(Vec<Field<'a>>),
+    Enum(Tag, Vec<Variant<'a>>),
+}
+
+// The integer written before the fields of an enum variant.
+pub struct Tag {
+    pub ty: Ident,
+}
+
+pub struct Variant<'a> {
+    pub ident: &'a Ident,
+    // The tag value of this variant, as an expression of the tag type.
+    pub tag: TokenStream,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    // Position of the field in its struct or variant.
+    pub index: usize,
+    pub ty: &'a Type,
+    pub original: &'a syn::Field,
+    // Marked `#[dignore]`: not part of the encoding.
+    pub ignored: bool,
+    // `#[disk(default = "path")]`: the function that produces the value of
+    // an ignored field when decoding, instead of `Default::default`.
+    pub default: Option<Path>,
+    // `#[disk(bound = "...")]`: replaces the bounds inferred from this field.
+    pub bound: Option<Vec<WherePredicate>>,
+    // `#[disk(size_with = path)]`, `#[disk(encode_with = path)]` and
+    // `#[disk(decode_with = path)]`: functions used in place of the runtime
+    // traits for a field whose type does not implement them.
+    pub size_with: Option<Path>,
+    pub encode_with: Option<Path>,
+    pub decode_with: Option<Path>,
+    // `#[disk(endian = "...")]`: overrides the byte order of the container.
+    pub endian: Option<Endian>,
+    // `#[disk(varint)]` or `#[disk(zigzag)]`: variable-length integers.
+    pub int: Option<IntEncoding>,
+    // `#[disk(len = "...")]`: overrides the length prefix of the container.
+    pub len: Option<LenPrefix>,
+    // `#[disk(align = N)]`: padding goes before the field so that it starts
+    // at a multiple of `N` from the start of the value.
+    pub align: Option<usize>,
+    // `#[disk(since = N)]` and `#[disk(until = N)]`: the first version the
+    // field is encoded in, and the first one it is no longer encoded in.
+    pub since: Option<LitInt>,
+    pub until: Option<LitInt>,
+    // `#[disk(bits = N)]`: the field takes `N` bits of an integer it shares
+    // with the `bits` fields next to it, starting at bit `shift`.
+    pub bits: Option<u32>,
+    pub shift: u32,
+    // Set on the first field of a run of `bits` fields, which the group is
+    // read and written with.
+    pub pack: Option<Pack>,
+}
+
+// An integer that a run of `#[disk(bits = N)]` fields is packed into.
+pub struct Pack {
+    // The smallest unsigned integer type that holds every field.
+    pub ty: Ident,
+    // The positions of the fields in their struct or variant, this one
+    // included.
+    pub members: Vec<usize>,
+}
+
+// The byte orders `#[disk(endian = "...")]` accepts, mirroring the runtime
+// crate's `Endian`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Endian {
+    Little,
+    Big,
+    Native,
+}
+
+// The length prefixes `#[disk(len = "...")]` accepts, mirroring the runtime
+// crate's `LenPrefix`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum LenPrefix {
+    U8,
+    U16,
+    U32,
+    Varint,
+}
+
+// The checksums `#[disk(checksum = "...")]` accepts.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Checksum {
+    Crc32c,
+    Xxh64,
+}
+
+impl Checksum {
+    // The integer type the checksum is stored as.
+    pub fn ty(self) -> Ident {
+        let ty = match self {
+            Checksum::Crc32c => "u32",
+            Checksum::Xxh64 => "u64",
+        };
+        Ident::new(ty, Span::call_site())
+    }
+
+    // An expression computing the checksum of `bytes` with the runtime crate.
+    pub fn compute(self, krate: &Path, bytes: TokenStream) -> TokenStream {
+        match self {
+            Checksum::Crc32c => quote!(#krate::checksum::crc32c(#bytes)),
+            Checksum::Xxh64 => quote!(#krate::checksum::xxh64(#bytes, 0)),
+        }
+    }
+}
+
+// The variable-length forms of `#[disk(varint)]` and `#[disk(zigzag)]`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum IntEncoding {
+    Varint,
+    Zigzag,
+}
+
+impl<'a> Container<'a> {
+    // Check every attribute of `input` and collect what the derives need.
+    // `derive` names the trait being derived, for error messages.
+    pub fn from_ast(input: &'a DeriveInput, derive: &str) -> syn::Result<Self> {
+        let mut errors = Errors::default();
+        check_attributes(input, &mut errors);
+
+        let krate = errors.check(crate_path(&input.attrs));
+        let mut bound = None;
+        let mut endian = None;
+        let mut len = None;
+        let mut align = None;
+        let mut version = None;
+        let mut checksum = None;
+        let mut transparent = None;
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident(DISK)) {
+            errors.check(attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("bound") {
+                    set_bound(&mut bound, &meta)
+                } else if meta.path.is_ident("endian") {
+                    set_endian(&mut endian, &meta)
+                } else if meta.path.is_ident("len") {
+                    set_len(&mut len, &meta)
+                } else if meta.path.is_ident("align") {
+                    set_align(&mut align, &meta)
+                } else if meta.path.is_ident("version") {
+                    set_version(&mut version, "version", &meta)
+                } else if meta.path.is_ident("checksum") {
+                    set_checksum(&mut checksum, &meta)
+                } else if meta.path.is_ident("transparent") {
+                    set_transparent(&mut transparent, &meta)
+                } else {
+                    Err(meta.error("unknown `disk` argument"))
+                }
+            }));
+        }
+        let body = match input.data {
+            Data::Struct(ref data) => Some(Body::Struct(fields(&data.fields, &mut errors))),
+            Data::Enum(ref data) => errors
+                .check(repr_type(&input.attrs))
+                .map(|repr| enum_body(repr, data, &mut errors)),
+            Data::Union(ref data) => {
+                errors.push(Error::new_spanned(
+                    data.union_token,
+                    format!("{} cannot be derived for unions", derive),
+                ));
+                None
+            }
+        };
+        if let Some(ref body) = body {
+            check_versions(version.as_ref(), body, &mut errors);
+        }
+        if let (Some(path), Some(body)) = (&transparent, &body) {
+            let conflicts = [
+                ("endian", endian.is_some()),
+                ("len", len.is_some()),
+                ("align", align.is_some()),
+                ("version", version.is_some()),
+                ("checksum", checksum.is_some()),
+            ];
+            check_transparent(path, &conflicts, body, &mut errors);
+        }
+        errors.finish()?;
+
+        Ok(Container {
+            ident: &input.ident,
+            generics: &input.generics,
+            krate: krate.unwrap(),
+            bound,
+            endian,
+            len,
+            align,
+            version: version.as_ref().map(version_of),
+            checksum,
+            repr_packed: is_packed(&input.attrs),
+            transparent: transparent.is_some(),
+            body: body.unwrap(),
+        })
+    }
+
+    // The options a field is encoded with, as an expression of the runtime
+    // crate's `Options` built on the caller's `options`: what the field sets
+    // overrides what the type sets, which overrides the caller. Passing no
+    // field gives the options of the type itself.
+    pub fn options(&self, field: Option<&Field>) -> TokenStream {
+        self.options_on(quote!(options), field)
+    }
+
+    // The options a field is encoded with when the caller passes the
+    // defaults, as a constant expression.
+    pub fn declared_options(&self, field: &Field) -> TokenStream {
+        let krate = &self.krate;
+        self.options_on(quote!(#krate::Options::DEFAULT), Some(field))
+    }
+
+    fn options_on(&self, base: TokenStream, field: Option<&Field>) -> TokenStream {
+        let krate = &self.krate;
+        let endian = field.and_then(|f| f.endian).or(self.endian).map(|endian| {
+            let endian = match endian {
+                Endian::Little => quote!(Little),
+                Endian::Big => quote!(Big),
+                Endian::Native => quote!(Native),
+            };
+            quote!(.with_endian(#krate::Endian::#endian))
+        });
+        let int = field.and_then(|f| f.int).map(|int| {
+            let int = match int {
+                IntEncoding::Varint => quote!(Varint),
+                IntEncoding::Zigzag => quote!(Zigzag),
+            };
+            quote!(.with_int(#krate::IntEncoding::#int))
+        });
+        let len = field.and_then(|f| f.len).or(self.len).map(|len| {
+            let len = match len {
+                LenPrefix::U8 => quote!(U8),
+                LenPrefix::U16 => quote!(U16),
+                LenPrefix::U32 => quote!(U32),
+                LenPrefix::Varint => quote!(Varint),
+            };
+            quote!(.with_len(#krate::LenPrefix::#len))
+        });
+        quote!(#base #endian #int #len)
+    }
+
+    // The options of the numbers that are always fixed width, such as the enum
+    // tag, the integer of a `bits` group and the checksum: only the byte order
+    // of the type, or else the caller's, applies to them.
+    pub fn fixed(&self) -> TokenStream {
+        let krate = &self.krate;
+        let options = self.options(None);
+        quote!(#krate::__private::fixed(#options))
+    }
+
+    // An expression that borrows `field` of a struct `self`. The fields of a
+    // packed struct are copied out first, which needs them to be `Copy` but
+    // never takes a reference to an unaligned field.
+    pub fn borrow(&self, field: &Field) -> TokenStream {
+        let member = &field.member;
+        if self.repr_packed {
+            quote_spanned!(field.original.span()=> &{ self.#member })
+        } else {
+            quote!(&self.#member)
+        }
+    }
+
+    // The one encoded field of a `#[disk(transparent)]` struct, which the
+    // derives delegate to.
+    pub fn inner(&self) -> Option<&Field<'a>> {
+        match self.body {
+            Body::Struct(ref fields) if self.transparent => encoded(fields).next(),
+            _ => None,
+        }
+    }
+
+    // Every field of the type, across all variants of an enum.
+    pub fn fields(&self) -> Box<dyn Iterator<Item = &Field<'a>> + '_> {
+        self.body.fields()
+    }
+}
+
+impl<'a> Body<'a> {
+    // Every field of the body, across all variants of an enum.
+    fn fields(&self) -> Box<dyn Iterator<Item = &Field<'a>> + '_> {
+        match *self {
+            Body::Struct(ref fields) => Box::new(fields.iter()),
+            Body::Enum(_, ref variants) => Box::new(variants.iter().flat_map(|v| &v.fields)),
+        }
+    }
+}
+
+impl<'a> Variant<'a> {
+    // A pattern matching this variant that binds every encoded field. Braced
+    // patterns work for every kind of variant and let ignored fields fall
+    // under `..`.
+    pub fn pattern(&self) -> TokenStream {
+        let ident = self.ident;
+        let members = encoded(&self.fields).map(|f| &f.member);
+        let bindings = encoded(&self.fields).map(Field::binding);
+        quote!(Self::#ident { #(#members: #bindings,)* .. })
+    }
+}
+
+impl<'a> Field<'a> {
+    // The names of the attributes set on the field that change how it is
+    // encoded on its own, for the checks of attributes that rule them out.
+    pub fn encoding_attributes(&self) -> Vec<&'static str> {
+        let attributes = [
+            ("size_with", self.size_with.is_some()),
+            ("encode_with", self.encode_with.is_some()),
+            ("decode_with", self.decode_with.is_some()),
+            ("endian", self.endian.is_some()),
+            ("varint", self.int == Some(IntEncoding::Varint)),
+            ("zigzag", self.int == Some(IntEncoding::Zigzag)),
+            ("len", self.len.is_some()),
+            ("align", self.align.is_some()),
+            ("since", self.since.is_some()),
+            ("until", self.until.is_some()),
+        ];
+        attributes
+            .into_iter()
+            .filter(|&(_, set)| set)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    // Whether the field is only encoded in some versions.
+    pub fn versioned(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    // Whether the field is encoded in `version`.
+    pub fn present_in(&self, version: u32) -> bool {
+        self.since
+            .as_ref()
+            .is_none_or(|since| version >= version_of(since))
+            && self
+                .until
+                .as_ref()
+                .is_none_or(|until| version < version_of(until))
+    }
+
+    // A condition on the `__version` local of the generated code that holds
+    // when the field is encoded, or `None` for a field that always is.
+    pub fn presence(&self) -> Option<TokenStream> {
+        let since = self.since.as_ref().map(|since| {
+            let since = Literal::u32_unsuffixed(version_of(since));
+            quote!(__version >= #since)
+        });
+        let until = self.until.as_ref().map(|until| {
+            let until = Literal::u32_unsuffixed(version_of(until));
+            quote!(__version < #until)
+        });
+        match (since, until) {
+            (Some(since), Some(until)) => Some(quote!(#since && #until)),
+            (since, until) => since.or(until),
+        }
+    }
+
+    // The local the generated code binds this field's value to. Positional so
+    // that field names like `_x` do not produce non-snake-case locals.
+    pub fn binding(&self) -> Ident {
+        format_ident!("__field{}", self.index)
+    }
+
+    // Whether the field is read and written as part of the group of an
+    // earlier `bits` field, rather than on its own.
+    pub fn packed(&self) -> bool {
+        self.bits.is_some() && self.pack.is_none()
+    }
+}
+
+// The fields that make up the on-disk encoding, in order. Fields marked
+// `#[dignore]` are skipped the same way whether they are named, positional or
+// part of an enum variant.
+pub fn encoded<'b, 'a>(fields: &'b [Field<'a>]) -> impl Iterator<Item = &'b Field<'a>> {
+    fields.iter().filter(|f| !f.ignored)
+}
+
+fn fields<'a>(fields: &'a Fields, errors: &mut Errors) -> Vec<Field<'a>> {
+    let mut fields: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let mut field = Field {
+                member: match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                },
+                index: i,
+                ty: &f.ty,
+                original: f,
+                ignored: f.attrs.iter().any(|a| a.path().is_ident(IGNORE)),
+                default: None,
+                bound: None,
+                size_with: None,
+                encode_with: None,
+                decode_with: None,
+                endian: None,
+                int: None,
+                len: None,
+                align: None,
+                since: None,
+                until: None,
+                bits: None,
+                shift: 0,
+                pack: None,
+            };
+            for attr in f.attrs.iter().filter(|a| a.path().is_ident(DISK)) {
+                errors.check(field_attr(&mut field, attr));
+            }
+            // Checked once every attribute is in, since `since` and `until`
+            // may come after `default`.
+            if let Some(ref path) = field.default {
+                if !field.ignored && !field.versioned() {
+                    errors.push(Error::new_spanned(
+                        path,
+                        "`default` only applies to `#[dignore]` fields and fields with `since` or `until`",
+                    ));
+                }
+            }
+            if field.bits.is_some() {
+                check_bits(&field, errors);
+            }
+            field
+        })
+        .collect();
+    pack_bits(&mut fields, errors);
+    fields
+}
+
+// A `bits` field is encoded as part of its group, so nothing that changes
+// how a field is encoded on its own applies to it.
+fn check_bits(field: &Field, errors: &mut Errors) {
+    for name in field.encoding_attributes() {
+        errors.push(Error::new_spanned(
+            field.original,
+            format!("`bits` cannot be combined with `{}`", name),
+        ));
+    }
+}
+
+// Group each run of consecutive `bits` fields, skipping `#[dignore]` fields,
+// and assign every field its place in the group.
+fn pack_bits(fields: &mut [Field], errors: &mut Errors) {
+    let mut run = Vec::new();
+    let mut total = 0;
+    for i in 0..=fields.len() {
+        match fields.get(i) {
+            Some(field) if field.ignored => continue,
+            Some(field) => match field.bits {
+                Some(bits) if total + bits <= 64 => {
+                    fields[i].shift = total;
+                    total += bits;
+                    run.push(i);
+                    continue;
+                }
+                Some(_) => errors.push(Error::new_spanned(
+                    field.original,
+                    "`bits` fields are packed into at most 64 bits",
+                )),
+                None => {}
+            },
+            None => {}
+        }
+        if let Some(&lead) = run.first() {
+            let ty = match total {
+                0..=8 => "u8",
+                9..=16 => "u16",
+                17..=32 => "u32",
+                _ => "u64",
+            };
+            fields[lead].pack = Some(Pack {
+                ty: Ident::new(ty, Span::call_site()),
+                members: std::mem::take(&mut run),
+            });
+            total = 0;
+        }
+    }
+}
+
+// Apply one `#[disk(..)]` attribute to `field`.
+fn field_attr(field: &mut Field, attr: &Attribute) -> syn::Result<()> {
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("default") {
+            set_path(&mut field.default, "default", &meta)
+        } else if meta.path.is_ident("bound") {
+            set_bound(&mut field.bound, &meta)
+        } else if meta.path.is_ident("size_with") {
+            set_with(field.ignored, &mut field.size_with, "size_with", &meta)
+        } else if meta.path.is_ident("encode_with") {
+            set_with(field.ignored, &mut field.encode_with, "encode_with", &meta)
+        } else if meta.path.is_ident("decode_with") {
+            set_with(field.ignored, &mut field.decode_with, "decode_with", &meta)
+        } else if meta.path.is_ident("endian") {
+            if field.ignored {
+                return Err(meta.error("`endian` does not apply to `#[dignore]` fields"));
+            }
+            set_endian(&mut field.endian, &meta)
+        } else if meta.path.is_ident("len") {
+            if field.ignored {
+                return Err(meta.error("`len` does not apply to `#[dignore]` fields"));
+            }
+            set_len(&mut field.len, &meta)
+        } else if meta.path.is_ident("align") {
+            if field.ignored {
+                return Err(meta.error("`align` does not apply to `#[dignore]` fields"));
+            }
+            set_align(&mut field.align, &meta)
+        } else if meta.path.is_ident("since") || meta.path.is_ident("until") {
+            let name = meta.path.get_ident().unwrap().to_string();
+            if field.ignored {
+                return Err(meta.error(format!("`{}` does not apply to `#[dignore]` fields", name)));
+            }
+            let slot = if name == "since" {
+                &mut field.since
+            } else {
+                &mut field.until
+            };
+            set_version(slot, &name, &meta)
+        } else if meta.path.is_ident("bits") {
+            if field.ignored {
+                return Err(meta.error("`bits` does not apply to `#[dignore]` fields"));
+            }
+            set_bits(&mut field.bits, &meta)
+        } else if meta.path.is_ident("varint") {
+            set_int(field, IntEncoding::Varint, &meta)
+        } else if meta.path.is_ident("zigzag") {
+            set_int(field, IntEncoding::Zigzag, &meta)
+        } else {
+            Err(meta.error("unknown `disk` argument"))
+        }
+    })
+}
+
+// Parse `name = path` or `name = "path"`.
+fn set_path(slot: &mut Option<Path>, name: &str, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value = meta.value()?;
+    let path = if value.peek(LitStr) {
+        let lit: LitStr = value.parse()?;
+        lit.parse().map_err(in_lit(&lit))?
+    } else {
+        value.parse()?
+    };
+    if slot.is_some() {
+        return Err(meta.error(format!("duplicate `{}` argument", name)));
+    }
+    *slot = Some(path);
+    Ok(())
+}
+
+// The `*_with` functions replace how a field is encoded, which means nothing
+// for a field that is not encoded.
+fn set_with(
+    ignored: bool,
+    slot: &mut Option<Path>,
+    name: &str,
+    meta: &ParseNestedMeta,
+) -> syn::Result<()> {
+    if ignored {
+        return Err(meta.error(format!("`{}` does not apply to `#[dignore]` fields", name)));
+    }
+    set_path(slot, name, meta)
+}
+
+// Parse `bound = "..."` into a list of where predicates.
+fn set_bound(bound: &mut Option<Vec<WherePredicate>>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let predicates: LitStr = meta.value()?.parse()?;
+    if bound.is_some() {
+        return Err(meta.error("duplicate `bound` argument"));
+    }
+    let predicates = predicates
+        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)
+        .map_err(in_lit(&predicates))?;
+    *bound = Some(predicates.into_iter().collect());
+    Ok(())
+}
+
+// Point an error from parsing the contents of `lit` at `lit`. Without this an
+// error at the end of the contents is reported at the derive.
+fn in_lit(lit: &LitStr) -> impl Fn(Error) -> Error + '_ {
+    move |error| Error::new(lit.span(), error)
+}
+
+// Parse `endian = "big" | "little" | "native"`.
+fn set_endian(slot: &mut Option<Endian>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitStr = meta.value()?.parse()?;
+    let endian = match value.value().as_str() {
+        "little" => Endian::Little,
+        "big" => Endian::Big,
+        "native" => Endian::Native,
+        _ => {
+            return Err(Error::new_spanned(
+                value,
+                "expected `endian = \"big\"`, `\"little\"` or `\"native\"`",
+            ))
+        }
+    };
+    if slot.is_some() {
+        return Err(meta.error("duplicate `endian` argument"));
+    }
+    *slot = Some(endian);
+    Ok(())
+}
+
+// Parse `len = "u8" | "u16" | "u32" | "varint"`.
+fn set_len(slot: &mut Option<LenPrefix>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitStr = meta.value()?.parse()?;
+    let len = match value.value().as_str() {
+        "u8" => LenPrefix::U8,
+        "u16" => LenPrefix::U16,
+        "u32" => LenPrefix::U32,
+        "varint" => LenPrefix::Varint,
+        _ => {
+            return Err(Error::new_spanned(
+                value,
+                "expected `len = \"u8\"`, `\"u16\"`, `\"u32\"` or `\"varint\"`",
+            ))
+        }
+    };
+    if slot.is_some() {
+        return Err(meta.error("duplicate `len` argument"));
+    }
+    *slot = Some(len);
+    Ok(())
+}
+
+// Parse `align = N` for a power of two `N`.
+fn set_align(slot: &mut Option<usize>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitInt = meta.value()?.parse()?;
+    let align: usize = value.base10_parse()?;
+    if !align.is_power_of_two() {
+        return Err(Error::new_spanned(value, "`align` must be a power of two"));
+    }
+    if slot.is_some() {
+        return Err(meta.error("duplicate `align` argument"));
+    }
+    *slot = Some(align);
+    Ok(())
+}
+
+// Parse `bits = N` for a width from 1 to 64.
+fn set_bits(slot: &mut Option<u32>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitInt = meta.value()?.parse()?;
+    let bits: u32 = value.base10_parse()?;
+    if !(1..=64).contains(&bits) {
+        return Err(Error::new_spanned(value, "`bits` must be from 1 to 64"));
+    }
+    if slot.is_some() {
+        return Err(meta.error("duplicate `bits` argument"));
+    }
+    *slot = Some(bits);
+    Ok(())
+}
+
+// Parse `checksum = "crc32c" | "xxh64"`.
+fn set_checksum(slot: &mut Option<Checksum>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitStr = meta.value()?.parse()?;
+    let checksum = match value.value().as_str() {
+        "crc32c" => Checksum::Crc32c,
+        "xxh64" => Checksum::Xxh64,
+        _ => {
+            return Err(Error::new_spanned(
+                value,
+                "expected `checksum = \"crc32c\"` or `\"xxh64\"`",
+            ))
+        }
+    };
+    if slot.is_some() {
+        return Err(meta.error("duplicate `checksum` argument"));
+    }
+    *slot = Some(checksum);
+    Ok(())
+}
+
+// Parse `name = N` for a `u32` version number.
+fn set_version(slot: &mut Option<LitInt>, name: &str, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let value: LitInt = meta.value()?.parse()?;
+    if value.base10_parse::<u32>().is_err() {
+        return Err(Error::new_spanned(value, "expected a `u32` version number"));
+    }
+    if slot.is_some() {
+        return Err(meta.error(format!("duplicate `{}` argument", name)));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+// The value of a version number checked by `set_version`.
+pub fn version_of(lit: &LitInt) -> u32 {
+    lit.base10_parse().unwrap()
+}
+
+// `since` and `until` count in the versions of the type, so they need one,
+// and describe a field that is encoded in at least one version up to it.
+fn check_versions(version: Option<&LitInt>, body: &Body, errors: &mut Errors) {
+    for field in body.fields() {
+        let (since, until) = (field.since.as_ref(), field.until.as_ref());
+        let Some(version) = version else {
+            for lit in since.into_iter().chain(until) {
+                errors.push(Error::new_spanned(
+                    lit,
+                    "`since` and `until` need `#[disk(version = N)]` on the type",
+                ));
+            }
+            continue;
+        };
+        if let Some(since) = since {
+            if version_of(since) > version_of(version) {
+                errors.push(Error::new_spanned(
+                    since,
+                    format!("`since` is after the type's version {}", version),
+                ));
+            }
+        }
+        if let (Some(since), Some(until)) = (since, until) {
+            if version_of(until) <= version_of(since) {
+                errors.push(Error::new_spanned(until, "`until` must be after `since`"));
+            }
+        }
+    }
+}
+
+// Parse the bare `transparent` flag, keeping its path for error messages.
+fn set_transparent(slot: &mut Option<Path>, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+        return Err(meta.error("`transparent` does not take a value"));
+    }
+    if slot.is_some() {
+        return Err(meta.error("duplicate `transparent` argument"));
+    }
+    *slot = Some(meta.path.clone());
+    Ok(())
+}
+
+// A `transparent` struct has exactly one encoded field and adds nothing of its
+// own to the field's encoding, so neither the type nor the field may set
+// anything that changes it. `conflicts` are the type's own attributes.
+fn check_transparent(path: &Path, conflicts: &[(&str, bool)], body: &Body, errors: &mut Errors) {
+    let fields = match body {
+        Body::Struct(fields) => fields,
+        Body::Enum(..) => {
+            errors.push(Error::new_spanned(
+                path,
+                "`transparent` only applies to structs",
+            ));
+            return;
+        }
+    };
+    for &(name, set) in conflicts {
+        if set {
+            errors.push(Error::new_spanned(
+                path,
+                format!("`transparent` cannot be combined with `{}`", name),
+            ));
+        }
+    }
+    let count = encoded(fields).count();
+    if count != 1 {
+        errors.push(Error::new_spanned(
+            path,
+            format!(
+                "`transparent` needs exactly one field that is not `#[dignore]`, found {}",
+                count
+            ),
+        ));
+        return;
+    }
+    let field = encoded(fields).next().unwrap();
+    let bits = field.bits.map(|_| "bits");
+    for name in field.encoding_attributes().into_iter().chain(bits) {
+        errors.push(Error::new_spanned(
+            field.original,
+            format!("`transparent` cannot be combined with `{}`", name),
+        ));
+    }
+}
+
+// Mark `field` as `varint` or `zigzag`, which are bare flags and exclusive.
+fn set_int(field: &mut Field, int: IntEncoding, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let name = meta.path.get_ident().unwrap().to_string();
+    if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+        return Err(meta.error(format!("`{}` does not take a value", name)));
+    }
+    if field.ignored {
+        return Err(meta.error(format!("`{}` does not apply to `#[dignore]` fields", name)));
+    }
+    match field.int {
+        Some(old) if old == int => Err(meta.error(format!("duplicate `{}` argument", name))),
+        Some(_) => Err(meta.error("`varint` and `zigzag` cannot be combined")),
+        None => {
+            field.int = Some(int);
+            Ok(())
+        }
+    }
+}
+
+// Pair every variant with its tag.
+//
+// An integer `#[repr(..)]` fixes the tag type and the tag is the variant's
+// discriminant, counting up from the last explicit one the way the compiler
+// does. Without one the tag is the variant's index, stored in the smallest
+// unsigned integer that can count every variant.
+fn enum_body<'a>(repr: Option<Ident>, data: &'a syn::DataEnum, errors: &mut Errors) -> Body<'a> {
+    let mut last: Option<&Expr> = None;
+    let mut offset = 0usize;
+    let variants = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let tag = if repr.is_none() {
+                let index = Literal::usize_unsuffixed(i);
+                quote!(#index)
+            } else {
+                if let Some((_, ref expr)) = variant.discriminant {
+                    last = Some(expr);
+                    offset = 0;
+                }
+                let delta = Literal::usize_unsuffixed(offset);
+                offset += 1;
+                match last {
+                    Some(expr) if offset == 1 => quote!(#expr),
+                    Some(expr) => quote!((#expr) + #delta),
+                    None => quote!(#delta),
+                }
+            };
+            Variant {
+                ident: &variant.ident,
+                tag,
+                fields: fields(&variant.fields, errors),
+            }
+        })
+        .collect();
+
+    let ty = repr.unwrap_or_else(|| {
+        let ty = if data.variants.len() <= 1 << 8 {
+            "u8"
+        } else if data.variants.len() <= 1 << 16 {
+            "u16"
+        } else {
+            "u32"
+        };
+        Ident::new(ty, Span::call_site())
+    });
+    Body::Enum(Tag { ty }, variants)
+}
+
+// The integer type named by the enum's `#[repr(..)]`, if any.
+fn repr_type(attrs: &[Attribute]) -> syn::Result<Option<Ident>> {
+    let mut repr = None;
+    let mut errors = Errors::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        // `repr` arguments that are not integer types (C, align(..), ...)
+        // do not affect the tag and are skipped. A malformed `repr` is
+        // already reported by the compiler, so parse errors are dropped.
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                match ident.to_string().as_str() {
+                    "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" => {
+                        repr = Some(ident.clone());
+                    }
+                    "usize" | "isize" => errors.push(Error::new_spanned(
+                        ident,
+                        format!("`#[repr({})]` has no fixed on-disk width", ident),
+                    )),
+                    _ => {}
+                }
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+    }
+    errors.finish()?;
+    Ok(repr)
+}
+
+// Whether a `#[repr(..)]` packs the type. Like `repr_type`, malformed
+// attributes are left to the compiler.
+fn is_packed(attrs: &[Attribute]) -> bool {
+    let mut packed = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("packed") {
+                packed = true;
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+    }
+    packed
+}
+
+// Resolve the path the generated impls use to reach the runtime traits.
+//
+// In order of preference this is the path given by
+// `#[sized_on_disk(crate = "...")]`, the runtime crate under whatever name the
+// caller's Cargo.toml gives it, and finally the `crate::types` module of the
+// calling crate.
+fn crate_path(attrs: &[Attribute]) -> syn::Result<Path> {
+    let mut krate = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident(CONTAINER)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let path: LitStr = meta.value()?.parse()?;
+                if krate.is_some() {
+                    return Err(meta.error("duplicate `crate` argument"));
+                }
+                krate = Some(path.parse().map_err(in_lit(&path))?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown `sized_on_disk` argument, expected `crate`"))
+            }
+        })?;
+    }
+    if let Some(krate) = krate {
+        return Ok(krate);
+    }
+    Ok(match crate_name(RUNTIME_CRATE) {
+        // The runtime crate declares `extern crate self as ser`, so the
+        // absolute path also works inside it, including in its doctests.
+        Ok(FoundCrate::Itself) => {
+            let ident = Ident::new(RUNTIME_CRATE, Span::call_site());
+            parse_quote!(::#ident)
+        }
+        Ok(FoundCrate::Name(name)) => {
+            let ident = Ident::new(&name, Span::call_site());
+            parse_quote!(::#ident)
+        }
+        Err(_) => parse_quote!(crate::types),
+    })
+}
+
+// `#[dignore]` only makes sense on a field, `#[disk(..)]` on a field or the
+// type, and `#[sized_on_disk(..)]` only on the type itself.
+fn check_attributes(input: &DeriveInput, errors: &mut Errors) {
+    let no_ignore = |attrs: &[Attribute], errors: &mut Errors| {
+        for attr in attrs.iter().filter(|a| a.path().is_ident(IGNORE)) {
+            errors.push(Error::new_spanned(
+                attr,
+                "`#[dignore]` is only allowed on fields",
+            ));
+        }
+    };
+    let no_disk = |attrs: &[Attribute], errors: &mut Errors| {
+        for attr in attrs.iter().filter(|a| a.path().is_ident(DISK)) {
+            errors.push(Error::new_spanned(
+                attr,
+                "`#[disk(..)]` is not allowed on enum variants",
+            ));
+        }
+    };
+    let no_container = |attrs: &[Attribute], errors: &mut Errors| {
+        for attr in attrs.iter().filter(|a| a.path().is_ident(CONTAINER)) {
+            errors.push(Error::new_spanned(
+                attr,
+                "`#[sized_on_disk(..)]` is only allowed on the type",
+            ));
+        }
+    };
+    let fields = |fields: &Fields, errors: &mut Errors| {
+        for field in fields {
+            no_container(&field.attrs, errors);
+        }
+        let attrs = fields.iter().flat_map(|f| &f.attrs);
+        for attr in attrs.filter(|a| a.path().is_ident(IGNORE)) {
+            if attr.meta.require_path_only().is_err() {
+                errors.push(Error::new_spanned(
+                    &attr.meta,
+                    "`#[dignore]` does not take arguments",
+                ));
+            }
+        }
+    };
+
+    no_ignore(&input.attrs, errors);
+    match input.data {
+        Data::Struct(ref data) => fields(&data.fields, errors),
+        Data::Enum(ref data) => {
+            for variant in &data.variants {
+                no_ignore(&variant.attrs, errors);
+                no_disk(&variant.attrs, errors);
+                no_container(&variant.attrs, errors);
+                fields(&variant.fields, errors);
+            }
+        }
+        Data::Union(_) => {}
+    }
+}
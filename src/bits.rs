@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error};
+
+use crate::ast::{Body, Container};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    // Only the tag of a fieldless enum fits in a few bits.
+    match input.data {
+        Data::Enum(ref data) => {
+            let mut errors = crate::Errors::default();
+            for variant in data.variants.iter().filter(|v| !v.fields.is_empty()) {
+                errors.push(Error::new_spanned(
+                    &variant.fields,
+                    "Bits can only be derived for enums without fields",
+                ));
+            }
+            errors.finish()?;
+        }
+        Data::Struct(ref data) => {
+            return Err(Error::new_spanned(
+                data.struct_token,
+                "Bits can only be derived for enums without fields",
+            ));
+        }
+        Data::Union(_) => {}
+    }
+    let cont = Container::from_ast(input, "Bits")?;
+    let krate = &cont.krate;
+    let name = cont.ident;
+    let (tag, variants) = match cont.body {
+        Body::Enum(ref tag, ref variants) => (&tag.ty, variants),
+        Body::Struct(_) => unreachable!("rejected above"),
+    };
+    let (impl_generics, ty_generics, where_clause) = cont.generics.split_for_impl();
+
+    // The variant is stored as its tag, the same one `ToDisk` writes.
+    let arms = variants.iter().map(|variant| {
+        let ident = variant.ident;
+        let tag = &variant.tag;
+        quote!(Self::#ident => #tag,)
+    });
+    let checks = variants.iter().map(|variant| {
+        let ident = variant.ident;
+        let tag = &variant.tag;
+        quote! {
+            if __tag == #tag {
+                return ::core::result::Result::Ok(Self::#ident);
+            }
+        }
+    });
+    let name_str = name.to_string();
+
+    Ok(quote! {
+        impl #impl_generics #krate::Bits for #name #ty_generics #where_clause {
+            fn to_bits(&self, width: u32) -> ::core::option::Option<u64> {
+                let __tag: #tag = match *self {
+                    #(#arms)*
+                };
+                #krate::Bits::to_bits(&__tag, width)
+            }
+
+            fn from_bits(
+                bits: u64,
+                width: u32,
+            ) -> ::core::result::Result<Self, #krate::DecodeError> {
+                let __tag = <#tag as #krate::Bits>::from_bits(bits, width)?;
+                #(#checks)*
+                ::core::result::Result::Err(#krate::DecodeError::InvalidTag {
+                    ty: #name_str,
+                    tag: __tag as i128,
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,107 @@
+//! Inference of the where clause of the generated impls.
+
+use std::collections::HashSet;
+
+use syn::visit::{self, Visit};
+use syn::{parse_quote, Generics, Ident, Macro, Path, TypePath, WherePredicate};
+
+use crate::ast::{Container, Field};
+
+// Selects the fields whose types a bound is inferred from.
+pub type FieldFilter = fn(&Field) -> bool;
+
+// The generics of a generated impl: the type's own plus either the bounds
+// given by `#[disk(bound = "...")]` on the type, or the bounds each
+// `(filter, trait)` pair infers from the fields `filter` selects.
+//
+// Like serde, only the type parameters that a selected field's type
+// mentions are bounded, `PhantomData<T>` does not count as a mention, and an
+// associated type such as `T::Item` is bounded itself instead of `T`. A
+// field's own `#[disk(bound = "...")]` replaces what its type would infer.
+pub fn with_bounds(cont: &Container, bounds: &[(FieldFilter, Path)]) -> Generics {
+    let mut generics = cont.generics.clone();
+    let predicates = match cont.bound {
+        Some(ref predicates) => predicates.clone(),
+        None => bounds
+            .iter()
+            .flat_map(|(filter, bound)| infer(cont, *filter, bound))
+            .collect(),
+    };
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
+fn infer(cont: &Container, filter: FieldFilter, bound: &Path) -> Vec<WherePredicate> {
+    let all_type_params = cont
+        .generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect();
+    let mut visitor = FindTyParams {
+        all_type_params,
+        relevant_type_params: HashSet::new(),
+        associated_type_usage: Vec::new(),
+    };
+    let mut explicit = Vec::new();
+    for field in cont.fields().filter(|f| filter(f)) {
+        match field.bound {
+            Some(ref predicates) => explicit.extend(predicates.iter().cloned()),
+            None => visitor.visit_type(field.ty),
+        }
+    }
+
+    // Declaration order keeps the generated where clause stable.
+    let params = cont
+        .generics
+        .type_params()
+        .map(|p| &p.ident)
+        .filter(|ident| visitor.relevant_type_params.contains(*ident))
+        .map(|ident| -> WherePredicate { parse_quote!(#ident: #bound) });
+    let associated = visitor
+        .associated_type_usage
+        .iter()
+        .map(|path| -> WherePredicate { parse_quote!(#path: #bound) });
+    params.chain(associated).chain(explicit).collect()
+}
+
+struct FindTyParams<'ast> {
+    // Every type parameter of the type being derived.
+    all_type_params: HashSet<Ident>,
+    // The ones mentioned by a field type outside of `PhantomData`.
+    relevant_type_params: HashSet<Ident>,
+    // Paths like `T::Item` that start with a type parameter.
+    associated_type_usage: Vec<&'ast TypePath>,
+}
+
+impl<'ast> Visit<'ast> for FindTyParams<'ast> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if ty.qself.is_none() && ty.path.segments.len() > 1 {
+            if let Some(first) = ty.path.segments.first() {
+                if self.all_type_params.contains(&first.ident) {
+                    self.associated_type_usage.push(ty);
+                    return;
+                }
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_path(&mut self, path: &'ast Path) {
+        if let Some(seg) = path.segments.last() {
+            if seg.ident == "PhantomData" {
+                // `PhantomData<T>` carries no `T` to encode.
+                return;
+            }
+        }
+        if path.leading_colon.is_none() && path.segments.len() == 1 {
+            let ident = &path.segments[0].ident;
+            if self.all_type_params.contains(ident) {
+                self.relevant_type_params.insert(ident.clone());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+
+    // Type macros cannot be looked into.
+    fn visit_macro(&mut self, _mac: &'ast Macro) {}
+}
@@ -0,0 +1,325 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, DeriveInput};
+
+use crate::ast::{Body, Container, Field, Pack};
+use crate::bound::with_bounds;
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let cont = Container::from_ast(input, "FromDisk")?;
+    let krate = &cont.krate;
+    let name = cont.ident;
+
+    // Bound the type parameters that the encoded fields use, and those of
+    // ignored or versioned fields that can be filled in with
+    // `Default::default()`.
+    let generics = with_bounds(
+        &cont,
+        &[
+            (
+                |f| !f.ignored && f.decode_with.is_none() && f.bits.is_none(),
+                parse_quote!(#krate::FromDisk),
+            ),
+            (|f| f.bits.is_some(), parse_quote!(#krate::Bits)),
+            (
+                |f| (f.ignored || f.versioned()) && f.default.is_none(),
+                parse_quote!(::core::default::Default),
+            ),
+        ],
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = disk_read(&cont);
+    let body = quote! {
+        let mut __n = 0;
+        #body
+    };
+
+    // A versioned type is read in any version by an inherent method, and in
+    // its current version by the trait.
+    let (versioned, current) = match cont.version {
+        Some(version) => {
+            let version = Literal::u32_unsuffixed(version);
+            let versioned = quote! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Reads a value encoded as of `version` from the start of
+                    /// `buf` and returns it together with the number of bytes
+                    /// it occupied. Fields the version does not have are
+                    /// filled in with their defaults.
+                    pub fn read_from_version(
+                        buf: &[u8],
+                        version: u32,
+                    ) -> ::core::result::Result<(Self, usize), #krate::DecodeError> {
+                        let __version = version;
+                        let options = #krate::Options::DEFAULT;
+                        #body
+                    }
+                }
+            };
+            (
+                Some(versioned),
+                Some(quote!(let __version: u32 = #version;)),
+            )
+        }
+        None => (None, None),
+    };
+
+    let items = match (cont.inner(), &cont.body) {
+        (Some(inner), Body::Struct(fields)) => transparent(&cont, fields, inner),
+        _ => quote! {
+            fn read_from(buf: &[u8]) -> ::core::result::Result<(Self, usize), #krate::DecodeError> {
+                Self::read_in(buf, #krate::Options::DEFAULT)
+            }
+
+            fn read_in(
+                buf: &[u8],
+                options: #krate::Options,
+            ) -> ::core::result::Result<(Self, usize), #krate::DecodeError> {
+                #current
+                #body
+            }
+        },
+    };
+
+    Ok(quote! {
+        #versioned
+
+        impl #impl_generics #krate::FromDisk for #name #ty_generics #where_clause {
+            #items
+        }
+    })
+}
+
+// The items of a `#[disk(transparent)]` struct's impl, which hand every call
+// and its options to the one encoded field and fill in the ignored ones.
+fn transparent(cont: &Container, fields: &[Field], inner: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let binding = inner.binding();
+    let ty = inner.ty;
+    let ty = quote_spanned!(inner.original.span()=> <#ty as #krate::FromDisk>);
+    let defaults: Vec<_> = fields
+        .iter()
+        .filter(|f| f.ignored)
+        .map(|f| read_field(cont, fields, f))
+        .collect();
+    let construct = construct(quote!(Self), fields);
+    quote! {
+        fn read_from(buf: &[u8]) -> ::core::result::Result<(Self, usize), #krate::DecodeError> {
+            let (#binding, __n) = #ty::read_from(buf)?;
+            #(#defaults)*
+            ::core::result::Result::Ok((#construct, __n))
+        }
+
+        fn read_in(
+            buf: &[u8],
+            options: #krate::Options,
+        ) -> ::core::result::Result<(Self, usize), #krate::DecodeError> {
+            let (#binding, __n) = #ty::read_in(buf, options)?;
+            #(#defaults)*
+            ::core::result::Result::Ok((#construct, __n))
+        }
+
+        fn skip_in(
+            buf: &[u8],
+            options: #krate::Options,
+        ) -> ::core::result::Result<usize, #krate::DecodeError> {
+            #ty::skip_in(buf, options)
+        }
+    }
+}
+
+// Generate statements that read each encoded field after the previous one,
+// keeping the running total in `__n`, and then build the value. The fields are
+// visited exactly as `disk_size_sum` visits them.
+fn disk_read(cont: &Container) -> TokenStream {
+    let krate = &cont.krate;
+    match cont.body {
+        Body::Struct(ref fields) => {
+            // Expands to statements like
+            //
+            //     let (__field0, __len) = <X as FromDisk>::read_in(&buf[__n..], options)?;
+            //     __n += __len;
+            //     ...
+            //     Ok((Self { x: __field0, y: __field1 }, __n))
+            let reads = fields.iter().map(|f| read_field(cont, fields, f));
+            let checksum = check_checksum(cont);
+            let end = cont.align.map(|align| skip_padding(cont, align));
+            let construct = construct(quote!(Self), fields);
+            quote! {
+                #(#reads)*
+                #checksum
+                #end
+                ::core::result::Result::Ok((#construct, __n))
+            }
+        }
+        Body::Enum(ref tag, ref variants) => {
+            // Expands to a chain that reads the tag, compares it against the
+            // tag of each variant and reads the fields of the one it matches.
+            let name = cont.ident.to_string();
+            let tag_ty = &tag.ty;
+            let checksum = check_checksum(cont);
+            let end = cont.align.map(|align| skip_padding(cont, align));
+            let arms = variants.iter().map(|variant| {
+                let ident = variant.ident;
+                let tag = &variant.tag;
+                let reads = variant
+                    .fields
+                    .iter()
+                    .map(|f| read_field(cont, &variant.fields, f));
+                let construct = construct(quote!(Self::#ident), &variant.fields);
+                quote! {
+                    if __tag == #tag {
+                        #(#reads)*
+                        #checksum
+                        #end
+                        return ::core::result::Result::Ok((#construct, __n));
+                    }
+                }
+            });
+            let options = cont.fixed();
+            let read_tag = quote!(<#tag_ty as #krate::FromDisk>::read_in(buf, #options));
+            quote! {
+                let (__tag, __len) = #read_tag?;
+                __n += __len;
+                #(#arms)*
+                ::core::result::Result::Err(#krate::DecodeError::InvalidTag {
+                    ty: #name,
+                    tag: __tag as i128,
+                })
+            }
+        }
+    }
+}
+
+// Bind the decoded value of an encoded field of `fields` to its binding, or
+// the default of an ignored one or one the version being read does not have.
+// The first field of a `bits` group binds every field in it.
+fn read_field(cont: &Container, fields: &[Field], field: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let binding = field.binding();
+    let ty = field.ty;
+    let span = field.original.span();
+    if let Some(ref pack) = field.pack {
+        // Expands to
+        //
+        //     let (__bits, __len) = <u8 as FromDisk>::read_in(&buf[__n..], fixed(options))?;
+        //     __n += __len;
+        //     let __field0: X = Bits::from_bits(unpack(__bits as u64, 0, 1), 1)?;
+        //     let __field1: Y = Bits::from_bits(unpack(__bits as u64, 1, 3), 3)?;
+        let read = read_pack(cont, pack);
+        let unpacks = pack.members.iter().map(|&i| {
+            let member = &fields[i];
+            let binding = member.binding();
+            let ty = member.ty;
+            let unpack = unpack(cont, member);
+            quote_spanned!(member.original.span()=> let #binding: #ty = #unpack?;)
+        });
+        return quote! {
+            let (__bits, __len) = #read?;
+            __n += __len;
+            #(#unpacks)*
+        };
+    }
+    if field.packed() {
+        return TokenStream::new();
+    }
+    let default = match field.default {
+        Some(ref path) => quote_spanned!(span=> #path()),
+        None => quote_spanned!(span=> ::core::default::Default::default()),
+    };
+    if field.ignored {
+        quote! {
+            let #binding: #ty = #default;
+        }
+    } else {
+        let read = match field.decode_with {
+            Some(ref path) => quote_spanned!(span=> #path(&buf[__n..])),
+            None => {
+                let options = cont.options(Some(field));
+                quote_spanned! {span=>
+                    <#ty as #krate::FromDisk>::read_in(&buf[__n..], #options)
+                }
+            }
+        };
+        let pad = field.align.map(|align| skip_padding(cont, align));
+        match field.presence() {
+            Some(present) => quote_spanned! {span=>
+                let #binding: #ty = if #present {
+                    #pad
+                    let (#binding, __len): (#ty, usize) = #read?;
+                    __n += __len;
+                    #binding
+                } else {
+                    #default
+                };
+            },
+            None => quote_spanned! {span=>
+                #pad
+                let (#binding, __len): (#ty, usize) = #read?;
+                __n += __len;
+            },
+        }
+    }
+}
+
+// A call that reads the integer of a `bits` group at `__n`.
+pub fn read_pack(cont: &Container, pack: &Pack) -> TokenStream {
+    let krate = &cont.krate;
+    let ty = &pack.ty;
+    let options = cont.fixed();
+    quote!(<#ty as #krate::FromDisk>::read_in(&buf[__n..], #options))
+}
+
+// A call that extracts a field of a `bits` group from the group's integer in
+// `__bits`.
+pub fn unpack(cont: &Container, field: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let ty = field.ty;
+    let shift = field.shift;
+    let bits = field.bits.unwrap();
+    quote_spanned! {field.original.span()=>
+        <#ty as #krate::Bits>::from_bits(
+            #krate::__private::unpack(__bits as u64, #shift, #bits),
+            #bits,
+        )
+    }
+}
+
+// Read the stored checksum and compare it with the checksum of everything
+// read so far.
+pub fn check_checksum(cont: &Container) -> Option<TokenStream> {
+    let krate = &cont.krate;
+    let checksum = cont.checksum?;
+    let ty = checksum.ty();
+    let compute = checksum.compute(krate, quote!(&buf[..__n]));
+    let options = cont.fixed();
+    let read = quote!(<#ty as #krate::FromDisk>::read_in(&buf[__n..], #options));
+    Some(quote! {
+        let __computed: #ty = #compute;
+        let (__stored, __len) = #read?;
+        if __stored != __computed {
+            return ::core::result::Result::Err(#krate::DecodeError::ChecksumMismatch {
+                stored: __stored as u64,
+                computed: __computed as u64,
+            });
+        }
+        __n += __len;
+    })
+}
+
+// Skip up to the next multiple of `align`.
+pub fn skip_padding(cont: &Container, align: usize) -> TokenStream {
+    let krate = &cont.krate;
+    let align = Literal::usize_unsuffixed(align);
+    quote! {
+        __n = #krate::__private::skip_padding(buf, __n, #align)?;
+    }
+}
+
+// An expression that builds `path` out of the bindings of `fields`.
+fn construct(path: TokenStream, fields: &[Field]) -> TokenStream {
+    let members = fields.iter().map(|f| &f.member);
+    let bindings = fields.iter().map(Field::binding);
+    quote!(#path { #(#members: #bindings),* })
+}
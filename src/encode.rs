@@ -0,0 +1,279 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_quote, DeriveInput};
+
+use crate::ast::{encoded, Body, Container, Field};
+use crate::bound::with_bounds;
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let cont = Container::from_ast(input, "ToDisk")?;
+    let krate = &cont.krate;
+    let name = cont.ident;
+
+    // Bound the type parameters that the encoded fields use.
+    let generics = with_bounds(
+        &cont,
+        &[
+            (
+                |f| !f.ignored && f.encode_with.is_none() && f.bits.is_none(),
+                parse_quote!(#krate::ToDisk),
+            ),
+            (|f| f.bits.is_some(), parse_quote!(#krate::Bits)),
+        ],
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = disk_write(&cont);
+    let body = match cont.body {
+        // An enum without variants has no values to write, and nothing after
+        // the match would be reachable.
+        Body::Enum(_, ref variants) if variants.is_empty() => body,
+        _ => quote! {
+            let mut __n = 0;
+            #body
+            ::core::result::Result::Ok(__n)
+        },
+    };
+
+    // A versioned type is written in any version by an inherent method, and
+    // in its current version by the trait.
+    let (versioned, current) = match cont.version {
+        Some(version) => {
+            let version = Literal::u32_unsuffixed(version);
+            let versioned = quote! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Writes the encoding of `self` as of `version` to the
+                    /// start of `out` and returns the number of bytes written.
+                    pub fn write_to_version(
+                        &self,
+                        out: &mut [u8],
+                        version: u32,
+                    ) -> ::core::result::Result<usize, #krate::EncodeError> {
+                        let __version = version;
+                        let options = #krate::Options::DEFAULT;
+                        #body
+                    }
+                }
+            };
+            (
+                Some(versioned),
+                Some(quote!(let __version: u32 = #version;)),
+            )
+        }
+        None => (None, None),
+    };
+
+    let items = match cont.inner() {
+        Some(inner) => transparent(&cont, inner),
+        None => quote! {
+            fn write_to(
+                &self,
+                out: &mut [u8],
+            ) -> ::core::result::Result<usize, #krate::EncodeError> {
+                Self::write_in(self, out, #krate::Options::DEFAULT)
+            }
+
+            fn write_in(
+                &self,
+                out: &mut [u8],
+                options: #krate::Options,
+            ) -> ::core::result::Result<usize, #krate::EncodeError> {
+                #current
+                #body
+            }
+        },
+    };
+
+    Ok(quote! {
+        #versioned
+
+        impl #impl_generics #krate::ToDisk for #name #ty_generics #where_clause {
+            #items
+        }
+    })
+}
+
+// The items of a `#[disk(transparent)]` struct's impl, which hand every call
+// and its options to the one encoded field.
+fn transparent(cont: &Container, inner: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let value = cont.borrow(inner);
+    let ty = inner.ty;
+    let ty = quote_spanned!(inner.original.span()=> <#ty as #krate::ToDisk>);
+    quote! {
+        fn write_to(
+            &self,
+            out: &mut [u8],
+        ) -> ::core::result::Result<usize, #krate::EncodeError> {
+            #ty::write_to(#value, out)
+        }
+
+        fn write_in(
+            &self,
+            out: &mut [u8],
+            options: #krate::Options,
+        ) -> ::core::result::Result<usize, #krate::EncodeError> {
+            #ty::write_in(#value, out, options)
+        }
+    }
+}
+
+// Generate statements that write each encoded field after the previous one,
+// keeping the running total in `__n`. The fields are visited exactly as
+// `disk_size_sum` visits them.
+fn disk_write(cont: &Container) -> TokenStream {
+    match cont.body {
+        Body::Struct(ref fields) => {
+            // Expands to statements like
+            //
+            //     __n += ToDisk::write_in(&self.x, &mut out[__n..], options)?;
+            //     __n += ToDisk::write_in(&self.y, &mut out[__n..], options)?;
+            let value = |f: &Field| cont.borrow(f);
+            let writes = encoded(fields)
+                .filter(|f| !f.packed())
+                .map(|f| field_write(cont, fields, f, &value));
+            let checksum = write_checksum(cont);
+            let end = cont.align.map(|align| write_padding(cont, align));
+            quote! {
+                #(#writes)*
+                #checksum
+                #end
+            }
+        }
+        Body::Enum(ref tag, ref variants) => {
+            // Expands to a match that writes the tag and then the fields of
+            // the active variant.
+            let tag_ty = &tag.ty;
+            let write_tag = write(cont, None, quote!(&__tag));
+            let checksum = write_checksum(cont);
+            let end = cont.align.map(|align| write_padding(cont, align));
+            let arms = variants.iter().map(|variant| {
+                let pattern = variant.pattern();
+                let tag = &variant.tag;
+                let value = |f: &Field| f.binding().into_token_stream();
+                let writes = encoded(&variant.fields)
+                    .filter(|f| !f.packed())
+                    .map(|f| field_write(cont, &variant.fields, f, &value));
+                quote! {
+                    #pattern => {
+                        let __tag: #tag_ty = #tag;
+                        __n += #write_tag?;
+                        #(#writes)*
+                        #checksum
+                        #end
+                    }
+                }
+            });
+            if variants.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Write one encoded field of `fields`, given a function that borrows each
+// field. The first field of a `bits` group writes the whole group.
+fn field_write(
+    cont: &Container,
+    fields: &[Field],
+    field: &Field,
+    value: &dyn Fn(&Field) -> TokenStream,
+) -> TokenStream {
+    let krate = &cont.krate;
+    let pad = field.align.map(|align| write_padding(cont, align));
+    if let Some(ref pack) = field.pack {
+        // Expands to
+        //
+        //     let __bits = (0 | pack(Bits::to_bits(&self.x, 1), 0, 1)? | ...) as u8;
+        //     __n += ToDisk::write_in(&__bits, &mut out[__n..], fixed(options))?;
+        let ty = &pack.ty;
+        let parts = pack.members.iter().map(|&i| {
+            let member = &fields[i];
+            let value = value(member);
+            let shift = member.shift;
+            let bits = member.bits.unwrap();
+            let ty = member.ty;
+            quote_spanned! {member.original.span()=>
+                #krate::__private::pack(<#ty as #krate::Bits>::to_bits(#value, #bits), #shift, #bits)?
+            }
+        });
+        let write = write(cont, None, quote!(&__bits));
+        return quote! {
+            let __bits = (0 #(| #parts)*) as #ty;
+            __n += #write?;
+        };
+    }
+    let value = value(field);
+    let write = match field.encode_with {
+        Some(ref path) => quote_spanned! {field.original.span()=>
+            __n += #path(#value, &mut out[__n..])?;
+        },
+        None => {
+            let write = write(cont, Some(field), value);
+            quote_spanned! {field.original.span()=>
+                __n += #write?;
+            }
+        }
+    };
+    match field.presence() {
+        Some(present) => quote! {
+            if #present {
+                #pad
+                #write
+            }
+        },
+        None => quote! {
+            #pad
+            #write
+        },
+    }
+}
+
+// Write the checksum of everything written so far.
+fn write_checksum(cont: &Container) -> Option<TokenStream> {
+    let checksum = cont.checksum?;
+    let ty = checksum.ty();
+    let compute = checksum.compute(&cont.krate, quote!(&out[..__n]));
+    let write = write(cont, None, quote!(&__checksum));
+    Some(quote! {
+        let __checksum: #ty = #compute;
+        __n += #write?;
+    })
+}
+
+// Zero-fill up to the next multiple of `align`.
+fn write_padding(cont: &Container, align: usize) -> TokenStream {
+    let krate = &cont.krate;
+    let align = Literal::usize_unsuffixed(align);
+    quote! {
+        __n += #krate::__private::write_padding(out, __n, #align);
+    }
+}
+
+// A call that writes `value` at `__n` with the options of `field`, or with
+// the fixed-width options of the type for the enum tag, a `bits` group or the
+// checksum.
+fn write(cont: &Container, field: Option<&Field>, value: TokenStream) -> TokenStream {
+    let krate = &cont.krate;
+    match field {
+        Some(field) => {
+            // Naming the field's type puts an error about it on the field.
+            let ty = field.ty;
+            let options = cont.options(Some(field));
+            quote_spanned! {field.original.span()=>
+                <#ty as #krate::ToDisk>::write_in(#value, &mut out[__n..], #options)
+            }
+        }
+        None => {
+            let options = cont.fixed();
+            quote!(#krate::ToDisk::write_in(#value, &mut out[__n..], #options))
+        }
+    }
+}
@@ -1,103 +1,220 @@
-use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned};
-use syn::spanned::Spanned;
-use syn::{
-    parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, Generics, Index,
-};
+use syn::{parse_macro_input, DeriveInput, Error};
 
-#[proc_macro_derive(SizedOnDisk, attributes(dignore))]
+mod ast;
+mod bits;
+mod bound;
+mod decode;
+mod encode;
+mod schema;
+mod size;
+#[cfg(test)]
+mod tests;
+mod view;
+
+/// Derives `SizedOnDisk` by summing the sizes of a type's fields.
+///
+/// The derive also implements `checked_size`, which adds the same terms with
+/// `checked_add` and returns `None` instead of overflowing.
+///
+/// Fields marked `#[dignore]` are left out of the sum. An enum is sized as its
+/// tag plus the fields of the active variant. The tag is the discriminant in
+/// the enum's integer `#[repr(..)]`, or without one the variant's index in the
+/// smallest unsigned integer that can count every variant.
+///
+/// `#[disk(size_with = path)]` on a field sizes it with `path(&field)`, a
+/// `fn(&T) -> usize`, instead of `SizedOnDisk::size`. Use it for field types
+/// from other crates that do not implement the trait.
+///
+/// `#[disk(varint)]` or `#[disk(zigzag)]` on a field writes the integers in it,
+/// including those inside std containers and derived types, as LEB128. The
+/// field is sized with `SizedOnDisk::size_in`, which reports the exact length,
+/// and leaves the type without a `FIXED_SIZE`.
+///
+/// `#[disk(len = "u8" | "u16" | "u32" | "varint")]` on a field sets the width
+/// of the length prefixes of the strings and collections in it, and on the
+/// type sets it for every field that does not choose its own.
+///
+/// `#[disk(align = N)]` on a field pads before it with zeros so that it starts
+/// at a multiple of `N` bytes, and on the type pads the end so that the whole
+/// value is a multiple of `N` bytes long. `N` must be a power of two. Offsets
+/// are counted from the start of the value, which must itself be written at an
+/// aligned position for the field to be aligned on disk. The padding is part
+/// of `size()` and `FIXED_SIZE`.
+///
+/// `#[disk(version = N)]` on the type makes its encoding versioned: a field
+/// with `#[disk(since = S)]` is only encoded from version `S` on, and one
+/// with `#[disk(until = U)]` only before version `U`. The derive adds
+/// `VERSION` and `size_at_version(&self, version)` to the type, and the trait
+/// methods use version `N`. The version itself is not encoded.
+///
+/// `#[disk(checksum = "crc32c" | "xxh64")]` on the type appends a checksum of
+/// the bytes before it, as a `u32` or `u64` in the type's byte order and ahead
+/// of any `align` padding at the end. It counts towards `size()` and
+/// `FIXED_SIZE`.
+///
+/// `#[disk(transparent)]` on a struct with exactly one field that is not
+/// `#[dignore]` encodes the struct as that field. Every method, `FIXED_SIZE`
+/// and the options a caller passes go to the field, so `#[disk(endian = ..)]`
+/// on a field of a transparent type reaches the integer inside it. Nothing
+/// else that changes the encoding, on the type or the field, may be combined
+/// with it.
+///
+/// On a `#[repr(packed)]` struct the generated code copies each encoded field
+/// out before measuring or writing it, since a reference to an unaligned field
+/// is not allowed, so those fields must be `Copy`.
+///
+/// A type parameter is bounded by the trait only if an encoded field's type
+/// mentions it outside of `PhantomData`; an associated type like `T::Item` is
+/// bounded instead of `T`. `#[disk(bound = "T: Trait, ...")]` on a field
+/// replaces the bounds inferred from that field, and on the type replaces all
+/// of them.
+///
+/// `#[disk(bits = N)]` on consecutive fields packs them into one integer, the
+/// first field in the lowest `N` bits and each next one above it. The integer
+/// is the smallest of `u8`, `u16`, `u32` and `u64` that holds the whole group,
+/// is written in the type's byte order and counts once towards `size()` and
+/// `FIXED_SIZE`. `#[dignore]` fields in between do not break a group. The
+/// field types implement `Bits`, and a value too wide for its field is
+/// reported as `EncodeError::BitsOverflow`. Attributes that change how a field
+/// is encoded on its own, such as `varint` or `since`, do not apply to `bits`
+/// fields.
+///
+/// For a struct the derive also adds `OFFSET_<FIELD>` and `SIZE_<FIELD>`
+/// constants for each encoded field (`OFFSET_0`, `SIZE_0`, ... in a tuple
+/// struct), which give where the field starts and how many bytes it takes.
+/// They are computed from the same terms as `FIXED_SIZE`, so a constant that
+/// depends on a field without a fixed size fails to compile when it is used.
+/// A field with `size_with`, `varint` or `zigzag` has no `SIZE_` constant, and
+/// the fields after it have none at all.
+/// The fields of a `bits` group share the offset and size of the group.
+///
+/// Every derived type also gets `schema()`, which returns a `&'static
+/// Schema` describing the type and each of its fields, ignored ones included:
+/// the field's name, type, encoding attributes and fixed size.
+///
+/// The impl names the trait through the `ser` runtime crate, found under
+/// whatever name the caller's Cargo.toml gives it. Use
+/// `#[sized_on_disk(crate = "path")]` on the type to point it elsewhere.
+#[proc_macro_derive(SizedOnDisk, attributes(dignore, disk, sized_on_disk))]
 pub fn derive_disk_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
     let input = parse_macro_input!(input as DeriveInput);
 
-    // Used in the quasi-quotation below as `#name`.
-    let name = input.ident;
+    // Hand the output tokens back to the compiler, or every problem found in
+    // the input as `compile_error!` invocations.
+    size::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    // Add a bound `T: SizedOnDisk` to every type parameter T.
-    let generics = add_trait_bounds(input.generics);
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// Derives `ToDisk`, writing the fields that `SizedOnDisk` measures.
+///
+/// Fields are written in declaration order, skipping `#[dignore]` fields, and
+/// an enum writes its tag before the fields of the active variant, so the
+/// number of bytes written is always what `SizedOnDisk::size` reports. Accepts
+/// the same attributes as `#[derive(SizedOnDisk)]`. The first field that fails
+/// to encode, such as one whose length overflows its `#[disk(len = ..)]`
+/// prefix, fails the whole write.
+///
+/// A versioned type also gets `write_to_version(&self, out, version)`.
+///
+/// `#[disk(encode_with = path)]` on a field writes it with
+/// `path(&field, out)`, a `fn(&T, &mut [u8]) -> Result<usize, EncodeError>`,
+/// instead of `ToDisk::write_to`.
+///
+/// `#[disk(endian = "big" | "little" | "native")]` on the type sets the byte
+/// order of its numbers and enum tag, and on a field overrides it for that
+/// field. Fields are written with `ToDisk::write_in`, so the order also
+/// reaches the elements of std containers and the fields of derived types
+/// that do not set their own. The derived `write_in` uses the options it is
+/// given where the type and its fields set none, and `write_to` uses the
+/// defaults.
+#[proc_macro_derive(ToDisk, attributes(dignore, disk, sized_on_disk))]
+pub fn derive_to_disk(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    encode::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    // Generate an expression to sum up the heap size of each field.
-    let sum = disk_size_sum(&input.data);
+/// Derives `FromDisk`, reading back what `ToDisk` writes.
+///
+/// Fields are read in the order `SizedOnDisk` sums them. A `#[dignore]` field
+/// is not read; it is filled in with `Default::default()`, or by calling the
+/// function named by `#[disk(default = "path")]`. An enum tag that matches no
+/// variant is reported as `DecodeError::InvalidTag`.
+///
+/// A versioned type also gets `read_from_version(buf, version)`. Fields the
+/// version does not have are filled in like `#[dignore]` fields, so
+/// `#[disk(default = "path")]` applies to them as well.
+///
+/// A `#[disk(checksum = ..)]` that does not match the bytes read is reported
+/// as `DecodeError::ChecksumMismatch`.
+///
+/// `#[disk(decode_with = path)]` on a field reads it with `path(buf)`, a
+/// `fn(&[u8]) -> Result<(T, usize), DecodeError>`, instead of
+/// `FromDisk::read_from`.
+///
+/// The encoding attributes, such as `#[disk(endian = ..)]` and
+/// `#[disk(len = ..)]`, are read back the way `#[derive(ToDisk)]` writes them.
+#[proc_macro_derive(FromDisk, attributes(dignore, disk, sized_on_disk))]
+pub fn derive_from_disk(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    decode::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    let expanded = quote! {
-        // The generated impl.
-        impl #impl_generics crate::types::SizedOnDisk for #name #ty_generics #where_clause {
-            fn size(&self) -> usize {
-                #sum
-            }
-        }
-    };
+/// Derives a zero-copy view of a struct's encoding.
+///
+/// For a struct `Foo` this generates `FooRef<'a>`, which borrows the bytes
+/// `#[derive(ToDisk)]` writes. `FooRef::new(buf)` checks the encoding with
+/// `FromDisk::skip` and records where each field starts, and then one method
+/// per field, named after it or `_0`, `_1`, ... for tuple structs, decodes
+/// that field alone. A `String` field is returned as a `&'a str` and a
+/// `Vec<u8>` field without `varint` or `zigzag` as a `&'a [u8]`, both
+/// borrowed from the buffer. Fields marked `#[dignore]`, and those the current
+/// `#[disk(version = N)]` does not have, get no method. The field types need
+/// `FromDisk`, and every attribute of `#[derive(FromDisk)]` applies.
+#[proc_macro_derive(DiskView, attributes(dignore, disk, sized_on_disk))]
+pub fn derive_disk_view(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    view::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    // Hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+/// Derives `Bits` for an enum without fields, so it can be stored in a
+/// `#[disk(bits = N)]` field.
+///
+/// The variant is stored as the same tag `#[derive(ToDisk)]` writes for it, and
+/// a tag that matches no variant is reported as `DecodeError::InvalidTag`.
+#[proc_macro_derive(Bits, attributes(sized_on_disk))]
+pub fn derive_bits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    bits::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
-// Add a bound `T: SizedOnDisk` to every type parameter T.
-fn add_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param.bounds.push(parse_quote!(crate::types::SizedOnDisk));
+// Collects every error found in the input so they are all reported in one
+// pass instead of one per compile.
+#[derive(Default)]
+struct Errors(Option<Error>);
+
+impl Errors {
+    fn push(&mut self, error: Error) {
+        match self.0 {
+            Some(ref mut errors) => errors.combine(error),
+            None => self.0 = Some(error),
         }
     }
-    generics
-}
 
-// Generate an expression to sum up the heap size of each field.
-fn disk_size_sum(data: &Data) -> TokenStream {
-    match *data {
-        Data::Struct(ref data) => {
-            match data.fields {
-                Fields::Named(ref fields) => {
-                    // Expands to an expression like
-                    //
-                    //     0 + self.x.disk_size() + self.y.disk_size() + self.z.disk_size()
-                    //
-                    // but using fully qualified function call syntax.
-                    //
-                    // We take some care to use the span of each `syn::Field` as
-                    // the span of the corresponding `size`
-                    // call. This way if one of the field types does not
-                    // implement `SizedOnDisk` then the compiler's error message
-                    // underlines which field it is. An example is shown in the
-                    // readme of the parent directory.
-                    let attribute_name = "dignore";
-                    let recurse = fields.named.iter()
-                        .filter(|f| !f.attrs.iter().any(|a| 
-                                                       a.meta.require_path_only()
-                                                       .ok()
-                                                       .filter(|p| p.is_ident(attribute_name)).is_some()
-                                                       ))
-                        .map(|f| {
-                        let name = &f.ident;
-                        quote_spanned! {f.span()=>
-                            crate::types::SizedOnDisk::size(&self.#name)
-                        }
-                    });
-                    quote! {
-                        0 #(+ #recurse)*
-                    }
-                }
-                Fields::Unnamed(ref fields) => {
-                    // Expands to an expression like
-                    //
-                    //     0 + self.0.disk_size() + self.1.disk_size() + self.2.disk_size()
-                    let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let index = Index::from(i);
-                        quote_spanned! {f.span()=>
-                            crate::types::SizedOnDisk::size(&self.#index)
-                        }
-                    });
-                    quote! {
-                        0 #(+ #recurse)*
-                    }
-                }
-                Fields::Unit => {
-                    // Unit structs cannot own more than 0 bytes of heap memory.
-                    quote!(0)
-                }
-            }
-        }
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
+    fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        result.map_err(|e| self.push(e)).ok()
+    }
+
+    fn finish(self) -> syn::Result<()> {
+        self.0.map_or(Ok(()), Err)
     }
 }
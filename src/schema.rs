@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::{Member, Type};
+
+use crate::ast::{version_of, Body, Checksum, Container, Field, Pack};
+use crate::size::fixed_term;
+
+// Generate `schema()`, which describes every field in the order
+// `disk_size_sum` visits them, ignored ones included.
+pub fn schema(cont: &Container) -> TokenStream {
+    let krate = &cont.krate;
+    let name = cont.ident.unraw().to_string();
+    let layout = match cont.body {
+        Body::Struct(ref fields) => {
+            let fields = fields_schema(cont, fields);
+            quote!(#krate::schema::Layout::Struct(&[#(#fields),*]))
+        }
+        Body::Enum(ref tag, ref variants) => {
+            let tag_ty = tag.ty.to_string();
+            let variants = variants.iter().map(|variant| {
+                let name = variant.ident.unraw().to_string();
+                let tag = &variant.tag;
+                let fields = fields_schema(cont, &variant.fields);
+                quote! {
+                    #krate::schema::Variant {
+                        name: #name,
+                        tag: (#tag) as i128,
+                        fields: &[#(#fields),*],
+                    }
+                }
+            });
+            quote! {
+                #krate::schema::Layout::Enum {
+                    tag: #tag_ty,
+                    variants: &[#(#variants),*],
+                }
+            }
+        }
+    };
+    let version = option(cont.version.map(|version| quote!(#version)));
+    let checksum = option(cont.checksum.map(|checksum| match checksum {
+        Checksum::Crc32c => quote!("crc32c"),
+        Checksum::Xxh64 => quote!("xxh64"),
+    }));
+    let align = option(cont.align.map(|align| quote!(#align)));
+    let transparent = cont.transparent;
+    quote! {
+        /// A description of the encoding of this type.
+        pub fn schema() -> &'static #krate::Schema {
+            const {
+                &#krate::Schema {
+                    name: #name,
+                    layout: #layout,
+                    fixed_size: <Self as #krate::SizedOnDisk>::FIXED_SIZE,
+                    version: #version,
+                    checksum: #checksum,
+                    align: #align,
+                    transparent: #transparent,
+                }
+            }
+        }
+    }
+}
+
+// A `schema::Field` expression for each of `fields`.
+fn fields_schema(cont: &Container, fields: &[Field]) -> Vec<TokenStream> {
+    let krate = &cont.krate;
+    let mut pack: Option<&Pack> = None;
+    fields
+        .iter()
+        .map(|f| {
+            let name = match f.member {
+                Member::Named(ref ident) => ident.unraw().to_string(),
+                Member::Unnamed(ref index) => index.index.to_string(),
+            };
+            let ty = type_name(f.ty);
+            let ignored = f.ignored;
+            let options = cont.declared_options(f);
+            let align = option(f.align.map(|align| quote!(#align)));
+            let since = option(f.since.as_ref().map(version_of).map(|since| quote!(#since)));
+            let until = option(f.until.as_ref().map(version_of).map(|until| quote!(#until)));
+            let bits = option(f.bits.map(|bits| quote!(#bits)));
+            let custom =
+                f.size_with.is_some() || f.encode_with.is_some() || f.decode_with.is_some();
+            // The fields of a `bits` group all take the group's bytes.
+            if f.pack.is_some() {
+                pack = f.pack.as_ref();
+            }
+            let fixed_size = if f.ignored {
+                quote!(::core::option::Option::Some(0))
+            } else if f.packed() {
+                let ty = &pack.unwrap().ty;
+                quote!(::core::option::Option::Some(::core::mem::size_of::<#ty>()))
+            } else {
+                fixed_term(cont, f)
+            };
+            quote! {
+                #krate::schema::Field {
+                    name: #name,
+                    ty: #ty,
+                    ignored: #ignored,
+                    options: #options,
+                    align: #align,
+                    since: #since,
+                    until: #until,
+                    bits: #bits,
+                    custom: #custom,
+                    fixed_size: #fixed_size,
+                }
+            }
+        })
+        .collect()
+}
+
+fn option(value: Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+// The source form of `ty`. Token streams print a space between every pair of
+// tokens, so only the spaces a person would write are kept: between words,
+// after `,` and `;`, and around `+`, `=` and `->`.
+fn type_name(ty: &Type) -> String {
+    let tokens: Vec<char> = ty.to_token_stream().to_string().chars().collect();
+    let word = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+    let mut name = String::new();
+    for (i, &c) in tokens.iter().enumerate() {
+        if c == ' ' {
+            let (before, after) = (tokens[i - 1], tokens[i + 1]);
+            let arrow =
+                tokens[i + 1..].starts_with(&['-', '>']) || tokens[..i].ends_with(&['-', '>']);
+            let keep = word(before) && word(after)
+                || matches!(before, ',' | ';' | '+' | '=')
+                || matches!(after, '+' | '=')
+                || arrow;
+            if !keep {
+                continue;
+            }
+        }
+        name.push(c);
+    }
+    name
+}
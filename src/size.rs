@@ -0,0 +1,487 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_quote, DeriveInput, Ident, Member};
+
+use crate::ast::{encoded, Body, Container, Field};
+use crate::bound::with_bounds;
+use crate::schema::schema;
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let cont = Container::from_ast(input, "SizedOnDisk")?;
+    let krate = &cont.krate;
+
+    // Used in the quasi-quotation below as `#name`.
+    let name = cont.ident;
+
+    // Bound the type parameters that the encoded fields use.
+    let generics = with_bounds(
+        &cont,
+        &[(
+            |f| !f.ignored && f.size_with.is_none() && f.bits.is_none(),
+            parse_quote!(#krate::SizedOnDisk),
+        )],
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Generate an expression to sum up the heap size of each field, and one
+    // that adds the same terms with `checked_add`.
+    let sum = disk_size_sum(&cont, false);
+    let checked = disk_size_sum(&cont, true);
+    let fixed = fixed_size(&cont);
+    let constants = field_constants(&cont);
+    let schema = schema(&cont);
+
+    // A versioned type is measured for any version by an inherent method,
+    // and for its current version by the trait.
+    let (versioned, current) = match cont.version {
+        Some(version) => {
+            let version = Literal::u32_unsuffixed(version);
+            let versioned = quote! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// The version of the encoding the `SizedOnDisk`, `ToDisk`
+                    /// and `FromDisk` impls use.
+                    pub const VERSION: u32 = #version;
+
+                    /// The number of bytes `self` occupies when encoded as of
+                    /// `version`.
+                    pub fn size_at_version(&self, version: u32) -> usize {
+                        let __version = version;
+                        let options = #krate::Options::DEFAULT;
+                        #sum
+                    }
+                }
+            };
+            (
+                Some(versioned),
+                Some(quote!(let __version: u32 = #version;)),
+            )
+        }
+        None => (None, None),
+    };
+
+    let items = match cont.inner() {
+        Some(inner) => transparent(&cont, inner),
+        None => quote! {
+            const FIXED_SIZE: ::core::option::Option<usize> = #fixed;
+
+            fn size(&self) -> usize {
+                Self::size_in(self, #krate::Options::DEFAULT)
+            }
+
+            fn size_in(&self, options: #krate::Options) -> usize {
+                // Known at compile time when every field has a fixed size, so
+                // the walk below is only needed for variable layouts or
+                // options that change the width of numbers.
+                if let ::core::option::Option::Some(size) =
+                    #krate::__private::fixed_in(Self::FIXED_SIZE, options)
+                {
+                    return size;
+                }
+                #current
+                #sum
+            }
+
+            fn checked_size(&self) -> ::core::option::Option<usize> {
+                Self::checked_size_in(self, #krate::Options::DEFAULT)
+            }
+
+            fn checked_size_in(
+                &self,
+                options: #krate::Options,
+            ) -> ::core::option::Option<usize> {
+                if let ::core::option::Option::Some(size) =
+                    #krate::__private::fixed_in(Self::FIXED_SIZE, options)
+                {
+                    return ::core::option::Option::Some(size);
+                }
+                #current
+                #checked
+            }
+        },
+    };
+
+    Ok(quote! {
+        #versioned
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #constants
+            #schema
+        }
+
+        // The generated impl.
+        impl #impl_generics #krate::SizedOnDisk for #name #ty_generics #where_clause {
+            #items
+        }
+    })
+}
+
+// The items of a `#[disk(transparent)]` struct's impl, which hand every call
+// and its options to the one encoded field.
+fn transparent(cont: &Container, inner: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let fixed = fixed_term(cont, inner);
+    let value = cont.borrow(inner);
+    let ty = inner.ty;
+    let ty = quote_spanned!(inner.original.span()=> <#ty as #krate::SizedOnDisk>);
+    quote! {
+        const FIXED_SIZE: ::core::option::Option<usize> = #fixed;
+
+        fn size(&self) -> usize {
+            #ty::size(#value)
+        }
+
+        fn size_in(&self, options: #krate::Options) -> usize {
+            #ty::size_in(#value, options)
+        }
+
+        fn checked_size(&self) -> ::core::option::Option<usize> {
+            #ty::checked_size(#value)
+        }
+
+        fn checked_size_in(&self, options: #krate::Options) -> ::core::option::Option<usize> {
+            #ty::checked_size_in(#value, options)
+        }
+    }
+}
+
+// Generate a constant expression for `FIXED_SIZE` out of the `FIXED_SIZE` of
+// each field type, walking the fields the way `disk_size_sum` does for the
+// current version.
+fn fixed_size(cont: &Container) -> TokenStream {
+    let krate = &cont.krate;
+    let fields_sum = |fields: &[Field], tag: Option<&Ident>| {
+        let tag = tag
+            .into_iter()
+            .map(|ty| quote!(::core::option::Option::Some(::core::mem::size_of::<#ty>())));
+        // An aligned field starts a new sum out of the padded sum of the
+        // fields before it.
+        let mut terms: Vec<_> = tag.collect();
+        let current = encoded(fields).filter(|f| cont.version.is_none_or(|v| f.present_in(v)));
+        for f in current.filter(|f| !f.packed()) {
+            if let Some(align) = f.align {
+                let align = Literal::usize_unsuffixed(align);
+                terms = vec![quote! {
+                    #krate::__private::fixed_pad(#krate::__private::fixed_sum(&[#(#terms),*]), #align)
+                }];
+            }
+            terms.push(fixed_term(cont, f));
+        }
+        if let Some(checksum) = cont.checksum {
+            let ty = checksum.ty();
+            terms.push(quote!(::core::option::Option::Some(::core::mem::size_of::<#ty>())));
+        }
+        let sum = quote! {
+            #krate::__private::fixed_sum(&[#(#terms),*])
+        };
+        match cont.align {
+            Some(align) => {
+                let align = Literal::usize_unsuffixed(align);
+                quote!(#krate::__private::fixed_pad(#sum, #align))
+            }
+            None => sum,
+        }
+    };
+    match cont.body {
+        // Expands to `fixed_sum(&[<X as SizedOnDisk>::FIXED_SIZE, ...])`.
+        Body::Struct(ref fields) => fields_sum(fields, None),
+        // Every variant, tag included, has to come to the same fixed size.
+        Body::Enum(ref tag, ref variants) => {
+            let variants = variants
+                .iter()
+                .map(|v| fields_sum(&v.fields, Some(&tag.ty)));
+            quote! {
+                #krate::__private::fixed_same(&[#(#variants),*])
+            }
+        }
+    }
+}
+
+// The `FIXED_SIZE` of one encoded field, as an `Option<usize>` expression.
+pub fn fixed_term(cont: &Container, field: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let ty = field.ty;
+    if let Some(ref pack) = field.pack {
+        let ty = &pack.ty;
+        return quote!(::core::option::Option::Some(::core::mem::size_of::<#ty>()));
+    }
+    if variable(field) {
+        return quote!(::core::option::Option::None);
+    }
+    quote_spanned! {field.original.span()=>
+        <#ty as #krate::SizedOnDisk>::FIXED_SIZE
+    }
+}
+
+// Whether the size of a field varies whatever its type: nothing is known about
+// what a custom function returns, and variable-length integers depend on their
+// value.
+fn variable(field: &Field) -> bool {
+    field.size_with.is_some() || field.int.is_some()
+}
+
+// Generate `OFFSET_<FIELD>` and `SIZE_<FIELD>` constants for each encoded
+// field of a struct in its current version, out of the same terms as
+// `FIXED_SIZE`. Whether a field type has a fixed size is only known when the
+// constants are evaluated, so the constants of a field without one, and the
+// offsets after it, fail to compile when used instead of being left out.
+// A field that is variable whatever its type, such as a `varint` one, has no
+// `SIZE_` constant and ends the constants, since the compiler may evaluate a
+// constant that fails regardless of its type even when it is unused.
+// Enums have no fixed offsets and get none. The fields of a `bits` group all
+// get the offset and size of the group.
+fn field_constants(cont: &Container) -> Option<TokenStream> {
+    let krate = &cont.krate;
+    let fields = match cont.body {
+        Body::Struct(ref fields) => fields,
+        Body::Enum(..) => return None,
+    };
+    // The terms of the fields so far, as in `fixed_size`. Offsets are summed
+    // from them rather than from the constants before, since naming a constant
+    // that fails to evaluate fails the constant naming it even when neither is
+    // used.
+    let mut terms = Vec::new();
+    let mut group = None;
+    let mut constants = Vec::new();
+    let current = encoded(fields).filter(|f| cont.version.is_none_or(|v| f.present_in(v)));
+    for f in current {
+        let (name, suffix) = match f.member {
+            Member::Named(ref ident) => {
+                let name = ident.unraw().to_string();
+                let suffix = name.to_uppercase();
+                (name, suffix)
+            }
+            Member::Unnamed(ref index) => (index.index.to_string(), index.index.to_string()),
+        };
+        let offset = format_ident!("OFFSET_{}", suffix);
+        let size = format_ident!("SIZE_{}", suffix);
+        let offset_doc = format!("The offset of `{}` in the encoding.", name);
+        let size_doc = format!("The encoded size of `{}`.", name);
+        if f.packed() {
+            let (ref group_offset, ref group_size) = group.clone().unwrap();
+            constants.push(quote! {
+                #[doc = #offset_doc]
+                pub const #offset: usize = #group_offset;
+
+                #[doc = #size_doc]
+                pub const #size: usize = Self::#group_size;
+            });
+            continue;
+        }
+        if let Some(align) = f.align {
+            let align = Literal::usize_unsuffixed(align);
+            terms = vec![quote! {
+                #krate::__private::fixed_pad(#krate::__private::fixed_sum(&[#(#terms),*]), #align)
+            }];
+        }
+        let message = format!("`{}` has no fixed offset", name);
+        let start = if terms.is_empty() {
+            quote!(0)
+        } else {
+            quote! {
+                #krate::__private::fixed_offset(#krate::__private::fixed_sum(&[#(#terms),*]), #message)
+            }
+        };
+        constants.push(quote! {
+            #[doc = #offset_doc]
+            pub const #offset: usize = #start;
+        });
+        if variable(f) {
+            break;
+        }
+        let term = fixed_term(cont, f);
+        let message = format!("`{}` has no fixed size", name);
+        constants.push(quote! {
+            #[doc = #size_doc]
+            pub const #size: usize = match #term {
+                ::core::option::Option::Some(size) => size,
+                ::core::option::Option::None => ::core::panic!(#message),
+            };
+        });
+        terms.push(term);
+        group = Some((start, size));
+    }
+    Some(quote!(#(#constants)*))
+}
+
+// Generate an expression to sum up the heap size of each field. With `checked`
+// the expression is an `Option<usize>` that is `None` when the sum overflows,
+// and it may return early with `?`.
+fn disk_size_sum(cont: &Container, checked: bool) -> TokenStream {
+    match cont.body {
+        Body::Struct(ref fields) => {
+            // Expands to an expression like
+            //
+            //     0 + self.x.disk_size() + self.y.disk_size() + self.z.disk_size()
+            //
+            // but using fully qualified function call syntax. Tuple structs
+            // use `self.0`, `self.1`, ... and unit structs are just `0`.
+            //
+            // We take some care to use the span of each `syn::Field` as
+            // the span of the corresponding `size`
+            // call. This way if one of the field types does not
+            // implement `SizedOnDisk` then the compiler's error message
+            // underlines which field it is. An example is shown in the
+            // readme of the parent directory.
+            sum_fields(cont, quote!(0), fields, checked, |f| cont.borrow(f))
+        }
+        Body::Enum(ref tag, ref variants) => {
+            // Expands to an expression like
+            //
+            //     match self {
+            //         Self::A { x: __field0, .. } => size_of::<u8>() + 0 + __field0.disk_size(),
+            //         Self::B { 0: __field0, .. } => size_of::<u8>() + 0 + __field0.disk_size(),
+            //         Self::C { .. } => size_of::<u8>() + 0,
+            //     }
+            //
+            // i.e. the width of the tag plus the size of the active variant.
+            let tag = &tag.ty;
+            let arms = variants.iter().map(|variant| {
+                let pattern = variant.pattern();
+                let tag = quote!(::core::mem::size_of::<#tag>() + 0);
+                let sum = sum_fields(cont, tag, &variant.fields, checked, |f| {
+                    f.binding().into_token_stream()
+                });
+                quote! {
+                    #pattern => #sum,
+                }
+            });
+            if variants.is_empty() {
+                // An enum without variants has no values to measure.
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Add the sizes of the encoded `fields` to `start`, given a function that
+// borrows each field.
+//
+// With `#[disk(align = N)]` on the type or a field this becomes a block that
+// keeps a running offset in `__n` and adds the padding before each aligned
+// field and at the end:
+//
+//     {
+//         let mut __n = 0;
+//         __n += self.x.disk_size();
+//         __n += padding(__n, 8);
+//         __n += self.y.disk_size();
+//         __n += padding(__n, 512);
+//         __n
+//     }
+//
+// With `checked` it always takes that form, with each `+=` replaced by
+// `__n = __n.checked_add(..)?`, and the result is wrapped in `Some`.
+fn sum_fields(
+    cont: &Container,
+    start: TokenStream,
+    fields: &[Field],
+    checked: bool,
+    value: impl Fn(&Field) -> TokenStream,
+) -> TokenStream {
+    let krate = &cont.krate;
+    let checksum = cont.checksum.map(|checksum| {
+        let ty = checksum.ty();
+        quote!(::core::mem::size_of::<#ty>())
+    });
+    // The fields of a `bits` group are counted once, with the first one.
+    let fields = || encoded(fields).filter(|f| !f.packed());
+    if !checked && cont.align.is_none() && fields().all(|f| f.align.is_none()) {
+        // A field that is only in some versions adds
+        // `if __version >= 2 { size } else { 0 }`.
+        let recurse = fields().map(|f| {
+            let size = field_size(cont, f, false, value(f));
+            match f.presence() {
+                Some(present) => quote!((if #present { #size } else { 0 })),
+                None => size,
+            }
+        });
+        let checksum = checksum.into_iter();
+        return quote! {
+            #start #(+ #recurse)* #(+ #checksum)*
+        };
+    }
+    let add = |size: TokenStream| match checked {
+        true => quote!(__n = __n.checked_add(#size)?;),
+        false => quote!(__n += #size;),
+    };
+    let pad = |align: usize| {
+        let align = Literal::usize_unsuffixed(align);
+        add(quote!(#krate::__private::padding(__n, #align)))
+    };
+    let steps: Vec<_> = fields()
+        .map(|f| {
+            let pad = f.align.map(pad);
+            let size = field_size(cont, f, checked, value(f));
+            let step = match checked {
+                true => add(quote!(#size?)),
+                false => add(size),
+            };
+            let step = quote! {
+                #pad
+                #step
+            };
+            match f.presence() {
+                Some(present) => quote!(if #present { #step }),
+                None => step,
+            }
+        })
+        .collect();
+    let checksum = checksum.map(add);
+    let end = cont.align.map(pad);
+    let sum = if steps.is_empty() && checksum.is_none() && end.is_none() {
+        // Nothing to add, and a `mut` binding that is never written to would
+        // warn.
+        start
+    } else {
+        quote! {
+            {
+                let mut __n: usize = #start;
+                #(#steps)*
+                #checksum
+                #end
+                __n
+            }
+        }
+    };
+    match checked {
+        true => quote!(::core::option::Option::Some(#sum)),
+        false => sum,
+    }
+}
+
+// The size of one encoded field, given an expression that borrows it, with
+// the options of the field. With `checked` the size is an `Option<usize>` from
+// `checked_size_in`.
+fn field_size(cont: &Container, field: &Field, checked: bool, value: TokenStream) -> TokenStream {
+    let krate = &cont.krate;
+    let size = if let Some(ref pack) = field.pack {
+        let ty = &pack.ty;
+        quote!(::core::mem::size_of::<#ty>())
+    } else if let Some(ref path) = field.size_with {
+        quote_spanned! {field.original.span()=>
+            #path(#value)
+        }
+    } else {
+        let ty = field.ty;
+        let size_in = match checked {
+            true => quote!(checked_size_in),
+            false => quote!(size_in),
+        };
+        let options = cont.options(Some(field));
+        return quote_spanned! {field.original.span()=>
+            <#ty as #krate::SizedOnDisk>::#size_in(#value, #options)
+        };
+    };
+    match checked {
+        true => quote!(::core::option::Option::Some(#size)),
+        false => size,
+    }
+}
@@ -0,0 +1,67 @@
+//! Golden-file tests of the code `#[derive(SizedOnDisk)]` generates.
+//!
+//! Each `tests/expand/<name>.rs` holds one type. Its expansion, pretty-printed,
+//! must match `tests/expand/<name>.expanded.rs`. Run with `EXPAND=overwrite`
+//! to write the expected files after an intended change to the output.
+//!
+//! The fixtures are expanded outside of any crate that depends on `ser`, so
+//! the impls name the runtime through the `crate::types` fallback.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use syn::DeriveInput;
+
+#[test]
+fn expand() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand");
+    let overwrite = env::var_os("EXPAND").is_some_and(|v| v == "overwrite");
+    let mut fixtures: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| !path.to_str().unwrap().ends_with(".expanded.rs"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no fixtures in {}", dir.display());
+
+    let mut failed = Vec::new();
+    for fixture in fixtures {
+        let source = fs::read_to_string(&fixture).unwrap();
+        let input: DeriveInput = syn::parse_str(&source).unwrap();
+        let tokens = crate::size::expand(&input).unwrap();
+        let file = syn::parse2(tokens).unwrap();
+        let actual = prettyplease::unparse(&file);
+
+        let expected_path = fixture.with_extension("expanded.rs");
+        if overwrite {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => {
+                let (line, (expected, actual)) = expected
+                    .lines()
+                    .chain([""])
+                    .zip(actual.lines().chain([""]))
+                    .enumerate()
+                    .find(|(_, (expected, actual))| expected != actual)
+                    .unwrap();
+                failed.push(format!(
+                    "{}: expansion changed at line {}\n  expected: {}\n    actual: {}",
+                    fixture.display(),
+                    line + 1,
+                    expected,
+                    actual,
+                ));
+            }
+            Err(_) => failed.push(format!("{}: no expanded file", fixture.display())),
+        }
+    }
+    assert!(
+        failed.is_empty(),
+        "{}\nrerun with EXPAND=overwrite to accept the new output",
+        failed.join("\n"),
+    );
+}
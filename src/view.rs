@@ -0,0 +1,303 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DeriveInput, Error, GenericArgument, GenericParam, Ident, Lifetime,
+    LifetimeParam, Member, PathArguments, Type,
+};
+
+use crate::ast::{encoded, Body, Container, Field, Pack};
+use crate::bound::with_bounds;
+use crate::decode::{check_checksum, read_pack, skip_padding, unpack};
+
+// Methods of the view that an accessor must not shadow.
+const RESERVED: &[&str] = &["new", "as_bytes"];
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(ref data) = input.data {
+        return Err(Error::new_spanned(
+            data.enum_token,
+            "DiskView can only be derived for structs",
+        ));
+    }
+    let cont = Container::from_ast(input, "DiskView")?;
+    let krate = &cont.krate;
+    let name = cont.ident;
+    let view = format_ident!("{}Ref", name);
+    let vis = &input.vis;
+
+    let all = match cont.body {
+        Body::Struct(ref fields) => fields,
+        Body::Enum(..) => unreachable!("rejected above"),
+    };
+    // The fields the view gives access to: those the current version
+    // encodes, in the order `disk_size_sum` visits them.
+    let fields: Vec<&Field> = encoded(all)
+        .filter(|f| cont.version.is_none_or(|v| f.present_in(v)))
+        .collect();
+    let mut errors = crate::Errors::default();
+    for f in &fields {
+        if let Member::Named(ref ident) = f.member {
+            if RESERVED.iter().any(|r| ident == r) {
+                errors.push(Error::new_spanned(
+                    ident,
+                    format!(
+                        "field `{}` collides with the `{}::{}` method",
+                        ident, view, ident
+                    ),
+                ));
+            }
+        }
+    }
+    errors.finish()?;
+
+    // The view borrows the buffer for `'a`, unless the type already has a
+    // lifetime of that name.
+    let taken = cont.generics.lifetimes().any(|l| l.lifetime.ident == "a");
+    let lifetime = Lifetime::new(if taken { "'__view" } else { "'a" }, Span::call_site());
+
+    let (_, ty_generics, where_clause) = cont.generics.split_for_impl();
+    let mut view_generics = cont.generics.clone();
+    view_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+    );
+    let (view_impl_generics, view_ty_generics, _) = view_generics.split_for_impl();
+
+    // The impl with the accessors needs the fields to decode.
+    let mut bounded = with_bounds(
+        &cont,
+        &[
+            (
+                |f| !f.ignored && f.decode_with.is_none() && f.bits.is_none(),
+                parse_quote!(#krate::FromDisk),
+            ),
+            (|f| f.bits.is_some(), parse_quote!(#krate::Bits)),
+        ],
+    );
+    bounded.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+    );
+    let (impl_generics, _, bounded_where) = bounded.split_for_impl();
+
+    let count = Literal::usize_unsuffixed(fields.len());
+    let walk = fields.iter().enumerate().map(|(i, f)| {
+        let i = Literal::usize_unsuffixed(i);
+        if f.packed() {
+            // Checked with the first field of its group, just before it.
+            return quote! {
+                __offsets[#i] = __offsets[#i - 1];
+            };
+        }
+        if let Some(ref pack) = f.pack {
+            let read = read_pack(&cont, pack);
+            let checks = pack.members.iter().map(|&i| {
+                let member = &all[i];
+                let ty = member.ty;
+                let unpack = unpack(&cont, member);
+                quote!(let _: #ty = #unpack?;)
+            });
+            return quote! {
+                __offsets[#i] = __n;
+                let (__bits, __len) = #read?;
+                #(#checks)*
+                __n += __len;
+            };
+        }
+        let pad = f.align.map(|align| skip_padding(&cont, align));
+        let skip = skip_field(&cont, f);
+        quote! {
+            #pad
+            __offsets[#i] = __n;
+            __n += #skip?;
+        }
+    });
+    let checksum = check_checksum(&cont);
+    let end = cont.align.map(|align| skip_padding(&cont, align));
+    // Each field of a `bits` group is read with the group of the first one.
+    let mut pack = None;
+    let accessors: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            if f.pack.is_some() {
+                pack = f.pack.as_ref();
+            }
+            accessor(&cont, f, i, pack.filter(|_| f.bits.is_some()), &lifetime)
+        })
+        .collect();
+
+    let doc = format!(
+        "A view of an encoded [`{}`] that decodes each field when it is accessed.",
+        name
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #view #view_impl_generics #where_clause {
+            buf: &#lifetime [u8],
+            // Where each field starts in `buf`.
+            offsets: [usize; #count],
+            marker: ::core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #view_impl_generics ::core::clone::Clone for #view #view_ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl #view_impl_generics ::core::marker::Copy for #view #view_ty_generics #where_clause {}
+
+        impl #impl_generics #view #view_ty_generics #bounded_where {
+            /// Checks that `buf` starts with a valid encoding and records
+            /// where each field starts, without decoding the fields.
+            pub fn new(
+                buf: &#lifetime [u8],
+            ) -> ::core::result::Result<Self, #krate::DecodeError> {
+                // A view reads the encoding the type writes on its own.
+                let options = #krate::Options::DEFAULT;
+                let mut __n = 0;
+                let mut __offsets = [0; #count];
+                #(#walk)*
+                #checksum
+                #end
+                ::core::result::Result::Ok(#view {
+                    buf: &buf[..__n],
+                    offsets: __offsets,
+                    marker: ::core::marker::PhantomData,
+                })
+            }
+
+            /// The bytes of the encoding the view was made from.
+            pub fn as_bytes(&self) -> &#lifetime [u8] {
+                self.buf
+            }
+
+            #(#accessors)*
+        }
+    })
+}
+
+// An expression that checks the encoding of `field` at `__n` and evaluates to
+// its length.
+fn skip_field(cont: &Container, field: &Field) -> TokenStream {
+    let krate = &cont.krate;
+    let ty = field.ty;
+    let span = field.original.span();
+    match field.decode_with {
+        Some(ref path) => quote_spanned! {span=>
+            #path(&buf[__n..]).map(|(_, __len): (#ty, usize)| __len)
+        },
+        None => {
+            let options = cont.options(Some(field));
+            quote_spanned! {span=>
+                <#ty as #krate::FromDisk>::skip_in(&buf[__n..], #options)
+            }
+        }
+    }
+}
+
+// A method that decodes the field at `offsets[index]`, out of the integer of
+// `pack` for a `bits` field, or borrows it from the buffer for `lifetime`.
+// Tuple fields are named `_0`, `_1`, ...
+fn accessor(
+    cont: &Container,
+    field: &Field,
+    index: usize,
+    pack: Option<&Pack>,
+    lifetime: &Lifetime,
+) -> TokenStream {
+    let krate = &cont.krate;
+    let ty = field.ty;
+    let span = field.original.span();
+    let method = match field.member {
+        Member::Named(ref ident) => ident.clone(),
+        Member::Unnamed(ref index) => format_ident!("_{}", index.index),
+    };
+    let vis = &field.original.vis;
+    let index = Literal::usize_unsuffixed(index);
+    let buf = quote!(&self.buf[self.offsets[#index]..]);
+    if let Some(pack) = pack {
+        // Every field of a `bits` group starts where the group does.
+        let read = read_pack(cont, pack);
+        let unpack = unpack(cont, field);
+        return quote! {
+            #vis fn #method(&self) -> #ty {
+                let (buf, __n) = (self.buf, self.offsets[#index]);
+                let options = #krate::Options::DEFAULT;
+                match #read.and_then(|(__bits, _)| #unpack) {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(_) => {
+                        ::core::unreachable!("the field was checked when the view was made")
+                    }
+                }
+            }
+        };
+    }
+    if let Some((target, helper)) = borrowed(field) {
+        let options = cont.options(Some(field));
+        return quote! {
+            #vis fn #method(&self) -> &#lifetime #target {
+                let options = #krate::Options::DEFAULT;
+                match #krate::__private::#helper(#buf, #options) {
+                    ::core::result::Result::Ok((value, _)) => value,
+                    ::core::result::Result::Err(_) => {
+                        ::core::unreachable!("the field was checked when the view was made")
+                    }
+                }
+            }
+        };
+    }
+    let read = match field.decode_with {
+        Some(ref path) => quote_spanned!(span=> #path(#buf)),
+        None => {
+            let options = cont.options(Some(field));
+            quote_spanned! {span=>
+                <#ty as #krate::FromDisk>::read_in(#buf, #options)
+            }
+        }
+    };
+    quote! {
+        #vis fn #method(&self) -> #ty {
+            let options = #krate::Options::DEFAULT;
+            match #read {
+                ::core::result::Result::Ok((value, _)) => value,
+                ::core::result::Result::Err(_) => {
+                    ::core::unreachable!("the field was checked when the view was made")
+                }
+            }
+        }
+    }
+}
+
+// The type a field's accessor borrows from the buffer instead of decoding,
+// and the `__private` function that finds it: `str` for a `String`, and
+// `[u8]` for a `Vec<u8>` whose bytes are written as they are, which an `int`
+// encoding changes. Fields with `decode_with` are always decoded.
+fn borrowed(field: &Field) -> Option<(TokenStream, Ident)> {
+    let last = match field.ty {
+        Type::Path(ref ty) if ty.qself.is_none() && field.decode_with.is_none() => {
+            ty.path.segments.last()?
+        }
+        _ => return None,
+    };
+    let byte = |arg: &GenericArgument| match arg {
+        GenericArgument::Type(Type::Path(ty)) => ty.qself.is_none() && ty.path.is_ident("u8"),
+        _ => false,
+    };
+    match last.arguments {
+        PathArguments::None if last.ident == "String" => {
+            Some((quote!(str), format_ident!("borrow_str")))
+        }
+        PathArguments::AngleBracketed(ref args)
+            if last.ident == "Vec" && args.args.len() == 1 && byte(&args.args[0]) =>
+        {
+            field
+                .int
+                .is_none()
+                .then(|| (quote!([u8]), format_ident!("borrow_bytes")))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,121 @@
+impl<T, U> Explicit<T, U>
+where
+    T: SizedOnDisk + Copy,
+{
+    ///The offset of `value` in the encoding.
+    pub const OFFSET_VALUE: usize = 0;
+    ///The encoded size of `value`.
+    pub const SIZE_VALUE: usize = match <T as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`value` has no fixed size"),
+    };
+    ///The offset of `other` in the encoding.
+    pub const OFFSET_OTHER: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[<T as crate::types::SizedOnDisk>::FIXED_SIZE],
+        ),
+        "`other` has no fixed offset",
+    );
+    ///The encoded size of `other`.
+    pub const SIZE_OTHER: usize = match <U as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`other` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Explicit",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "value",
+                            ty: "T",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <T as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "other",
+                            ty: "U",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <U as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl<T, U> crate::types::SizedOnDisk for Explicit<T, U>
+where
+    T: SizedOnDisk + Copy,
+{
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_sum(
+        &[
+            <T as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <U as crate::types::SizedOnDisk>::FIXED_SIZE,
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        0 + <T as crate::types::SizedOnDisk>::size_in(&self.value, options)
+            + <U as crate::types::SizedOnDisk>::size_in(&self.other, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        ::core::option::Option::Some({
+            let mut __n: usize = 0;
+            __n = __n
+                .checked_add(
+                    <T as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.value,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <U as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.other,
+                        options,
+                    )?,
+                )?;
+            __n
+        })
+    }
+}
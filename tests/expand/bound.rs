@@ -0,0 +1,6 @@
+#[disk(bound = "T: SizedOnDisk + Copy")]
+struct Explicit<T, U> {
+    value: T,
+    #[disk(bound = "U: Default")]
+    other: U,
+}
@@ -0,0 +1,174 @@
+impl Message {
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Message",
+                layout: crate::types::schema::Layout::Enum {
+                    tag: "u8",
+                    variants: &[
+                        crate::types::schema::Variant {
+                            name: "Ping",
+                            tag: (0) as i128,
+                            fields: &[],
+                        },
+                        crate::types::schema::Variant {
+                            name: "Data",
+                            tag: (1) as i128,
+                            fields: &[
+                                crate::types::schema::Field {
+                                    name: "0",
+                                    ty: "Vec<u8>",
+                                    ignored: false,
+                                    options: crate::types::Options::DEFAULT,
+                                    align: ::core::option::Option::None,
+                                    since: ::core::option::Option::None,
+                                    until: ::core::option::Option::None,
+                                    bits: ::core::option::Option::None,
+                                    custom: false,
+                                    fixed_size: <Vec<
+                                        u8,
+                                    > as crate::types::SizedOnDisk>::FIXED_SIZE,
+                                },
+                            ],
+                        },
+                        crate::types::schema::Variant {
+                            name: "Move",
+                            tag: (2) as i128,
+                            fields: &[
+                                crate::types::schema::Field {
+                                    name: "x",
+                                    ty: "i32",
+                                    ignored: false,
+                                    options: crate::types::Options::DEFAULT,
+                                    align: ::core::option::Option::None,
+                                    since: ::core::option::Option::None,
+                                    until: ::core::option::Option::None,
+                                    bits: ::core::option::Option::None,
+                                    custom: false,
+                                    fixed_size: <i32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                                },
+                                crate::types::schema::Field {
+                                    name: "y",
+                                    ty: "i32",
+                                    ignored: false,
+                                    options: crate::types::Options::DEFAULT,
+                                    align: ::core::option::Option::None,
+                                    since: ::core::option::Option::None,
+                                    until: ::core::option::Option::None,
+                                    bits: ::core::option::Option::None,
+                                    custom: false,
+                                    fixed_size: <i32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                                },
+                            ],
+                        },
+                    ],
+                },
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl crate::types::SizedOnDisk for Message {
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_same(
+        &[
+            crate::types::__private::fixed_sum(
+                &[::core::option::Option::Some(::core::mem::size_of::<u8>())],
+            ),
+            crate::types::__private::fixed_sum(
+                &[
+                    ::core::option::Option::Some(::core::mem::size_of::<u8>()),
+                    <Vec<u8> as crate::types::SizedOnDisk>::FIXED_SIZE,
+                ],
+            ),
+            crate::types::__private::fixed_sum(
+                &[
+                    ::core::option::Option::Some(::core::mem::size_of::<u8>()),
+                    <i32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                    <i32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                ],
+            ),
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        match self {
+            Self::Ping { .. } => ::core::mem::size_of::<u8>() + 0,
+            Self::Data { 0: __field0, .. } => {
+                ::core::mem::size_of::<u8>() + 0
+                    + <Vec<u8> as crate::types::SizedOnDisk>::size_in(__field0, options)
+            }
+            Self::Move { x: __field0, y: __field1, .. } => {
+                ::core::mem::size_of::<u8>() + 0
+                    + <i32 as crate::types::SizedOnDisk>::size_in(__field0, options)
+                    + <i32 as crate::types::SizedOnDisk>::size_in(__field1, options)
+            }
+        }
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        match self {
+            Self::Ping { .. } => {
+                ::core::option::Option::Some(::core::mem::size_of::<u8>() + 0)
+            }
+            Self::Data { 0: __field0, .. } => {
+                ::core::option::Option::Some({
+                    let mut __n: usize = ::core::mem::size_of::<u8>() + 0;
+                    __n = __n
+                        .checked_add(
+                            <Vec<
+                                u8,
+                            > as crate::types::SizedOnDisk>::checked_size_in(
+                                __field0,
+                                options,
+                            )?,
+                        )?;
+                    __n
+                })
+            }
+            Self::Move { x: __field0, y: __field1, .. } => {
+                ::core::option::Option::Some({
+                    let mut __n: usize = ::core::mem::size_of::<u8>() + 0;
+                    __n = __n
+                        .checked_add(
+                            <i32 as crate::types::SizedOnDisk>::checked_size_in(
+                                __field0,
+                                options,
+                            )?,
+                        )?;
+                    __n = __n
+                        .checked_add(
+                            <i32 as crate::types::SizedOnDisk>::checked_size_in(
+                                __field1,
+                                options,
+                            )?,
+                        )?;
+                    __n
+                })
+            }
+        }
+    }
+}
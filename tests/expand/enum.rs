@@ -0,0 +1,5 @@
+enum Message {
+    Ping,
+    Data(Vec<u8>),
+    Move { x: i32, y: i32 },
+}
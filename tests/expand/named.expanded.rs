@@ -0,0 +1,127 @@
+impl Entry {
+    ///The offset of `key` in the encoding.
+    pub const OFFSET_KEY: usize = 0;
+    ///The encoded size of `key`.
+    pub const SIZE_KEY: usize = match <u64 as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`key` has no fixed size"),
+    };
+    ///The offset of `value` in the encoding.
+    pub const OFFSET_VALUE: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[<u64 as crate::types::SizedOnDisk>::FIXED_SIZE],
+        ),
+        "`value` has no fixed offset",
+    );
+    ///The encoded size of `value`.
+    pub const SIZE_VALUE: usize = match <String as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`value` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Entry",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "key",
+                            ty: "u64",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <u64 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "value",
+                            ty: "String",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <String as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "cached",
+                            ty: "bool",
+                            ignored: true,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: ::core::option::Option::Some(0),
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl crate::types::SizedOnDisk for Entry {
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_sum(
+        &[
+            <u64 as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <String as crate::types::SizedOnDisk>::FIXED_SIZE,
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        0 + <u64 as crate::types::SizedOnDisk>::size_in(&self.key, options)
+            + <String as crate::types::SizedOnDisk>::size_in(&self.value, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        ::core::option::Option::Some({
+            let mut __n: usize = 0;
+            __n = __n
+                .checked_add(
+                    <u64 as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.key,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <String as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.value,
+                        options,
+                    )?,
+                )?;
+            __n
+        })
+    }
+}
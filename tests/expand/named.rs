@@ -0,0 +1,6 @@
+struct Entry {
+    key: u64,
+    value: String,
+    #[dignore]
+    cached: bool,
+}
@@ -0,0 +1,127 @@
+impl Header {
+    ///The offset of `magic` in the encoding.
+    pub const OFFSET_MAGIC: usize = 0;
+    ///The encoded size of `magic`.
+    pub const SIZE_MAGIC: usize = match <u8 as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`magic` has no fixed size"),
+    };
+    ///The offset of `len` in the encoding.
+    pub const OFFSET_LEN: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[<u8 as crate::types::SizedOnDisk>::FIXED_SIZE],
+        ),
+        "`len` has no fixed offset",
+    );
+    ///The encoded size of `len`.
+    pub const SIZE_LEN: usize = match <u32 as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`len` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Header",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "magic",
+                            ty: "u8",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <u8 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "len",
+                            ty: "u32",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <u32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "cached",
+                            ty: "Option<u16>",
+                            ignored: true,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: ::core::option::Option::Some(0),
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl crate::types::SizedOnDisk for Header {
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_sum(
+        &[
+            <u8 as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <u32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        0 + <u8 as crate::types::SizedOnDisk>::size_in(&{ self.magic }, options)
+            + <u32 as crate::types::SizedOnDisk>::size_in(&{ self.len }, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        ::core::option::Option::Some({
+            let mut __n: usize = 0;
+            __n = __n
+                .checked_add(
+                    <u8 as crate::types::SizedOnDisk>::checked_size_in(
+                        &{ self.magic },
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <u32 as crate::types::SizedOnDisk>::checked_size_in(
+                        &{ self.len },
+                        options,
+                    )?,
+                )?;
+            __n
+        })
+    }
+}
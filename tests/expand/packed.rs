@@ -0,0 +1,7 @@
+#[repr(C, packed)]
+struct Header {
+    magic: u8,
+    len: u32,
+    #[dignore]
+    cached: Option<u16>,
+}
@@ -0,0 +1,68 @@
+impl PageId {
+    ///The offset of `0` in the encoding.
+    pub const OFFSET_0: usize = 0;
+    ///The encoded size of `0`.
+    pub const SIZE_0: usize = match <u64 as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`0` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "PageId",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "0",
+                            ty: "u64",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <u64 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "1",
+                            ty: "bool",
+                            ignored: true,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: ::core::option::Option::Some(0),
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: true,
+            }
+        }
+    }
+}
+impl crate::types::SizedOnDisk for PageId {
+    const FIXED_SIZE: ::core::option::Option<usize> = <u64 as crate::types::SizedOnDisk>::FIXED_SIZE;
+    fn size(&self) -> usize {
+        <u64 as crate::types::SizedOnDisk>::size(&self.0)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        <u64 as crate::types::SizedOnDisk>::size_in(&self.0, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        <u64 as crate::types::SizedOnDisk>::checked_size(&self.0)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        <u64 as crate::types::SizedOnDisk>::checked_size_in(&self.0, options)
+    }
+}
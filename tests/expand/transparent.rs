@@ -0,0 +1,2 @@
+#[disk(transparent)]
+struct PageId(u64, #[dignore] bool);
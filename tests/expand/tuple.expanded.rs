@@ -0,0 +1,128 @@
+impl Pair {
+    ///The offset of `0` in the encoding.
+    pub const OFFSET_0: usize = 0;
+    ///The encoded size of `0`.
+    pub const SIZE_0: usize = match <u32 as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`0` has no fixed size"),
+    };
+    ///The offset of `2` in the encoding.
+    pub const OFFSET_2: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[<u32 as crate::types::SizedOnDisk>::FIXED_SIZE],
+        ),
+        "`2` has no fixed offset",
+    );
+    ///The encoded size of `2`.
+    pub const SIZE_2: usize = match <Vec<u16> as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`2` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Pair",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "0",
+                            ty: "u32",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <u32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "1",
+                            ty: "u8",
+                            ignored: true,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: ::core::option::Option::Some(0),
+                        },
+                        crate::types::schema::Field {
+                            name: "2",
+                            ty: "Vec<u16>",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <Vec<
+                                u16,
+                            > as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl crate::types::SizedOnDisk for Pair {
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_sum(
+        &[
+            <u32 as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <Vec<u16> as crate::types::SizedOnDisk>::FIXED_SIZE,
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        0 + <u32 as crate::types::SizedOnDisk>::size_in(&self.0, options)
+            + <Vec<u16> as crate::types::SizedOnDisk>::size_in(&self.2, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        ::core::option::Option::Some({
+            let mut __n: usize = 0;
+            __n = __n
+                .checked_add(
+                    <u32 as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.0,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <Vec<
+                        u16,
+                    > as crate::types::SizedOnDisk>::checked_size_in(&self.2, options)?,
+                )?;
+            __n
+        })
+    }
+}
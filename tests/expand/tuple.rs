@@ -0,0 +1 @@
+struct Pair(u32, #[dignore] u8, Vec<u16>);
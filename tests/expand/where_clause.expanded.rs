@@ -0,0 +1,212 @@
+impl<'a, K: Ord, V, I: Iterator, M> Table<'a, K, V, I, M>
+where
+    V: Clone,
+    K: crate::types::SizedOnDisk,
+    V: crate::types::SizedOnDisk,
+    I::Item: crate::types::SizedOnDisk,
+{
+    ///The offset of `keys` in the encoding.
+    pub const OFFSET_KEYS: usize = 0;
+    ///The encoded size of `keys`.
+    pub const SIZE_KEYS: usize = match <Vec<
+        K,
+    > as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`keys` has no fixed size"),
+    };
+    ///The offset of `values` in the encoding.
+    pub const OFFSET_VALUES: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[<Vec<K> as crate::types::SizedOnDisk>::FIXED_SIZE],
+        ),
+        "`values` has no fixed offset",
+    );
+    ///The encoded size of `values`.
+    pub const SIZE_VALUES: usize = match <&'a [V] as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`values` has no fixed size"),
+    };
+    ///The offset of `first` in the encoding.
+    pub const OFFSET_FIRST: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[
+                <Vec<K> as crate::types::SizedOnDisk>::FIXED_SIZE,
+                <&'a [V] as crate::types::SizedOnDisk>::FIXED_SIZE,
+            ],
+        ),
+        "`first` has no fixed offset",
+    );
+    ///The encoded size of `first`.
+    pub const SIZE_FIRST: usize = match <I::Item as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`first` has no fixed size"),
+    };
+    ///The offset of `marker` in the encoding.
+    pub const OFFSET_MARKER: usize = crate::types::__private::fixed_offset(
+        crate::types::__private::fixed_sum(
+            &[
+                <Vec<K> as crate::types::SizedOnDisk>::FIXED_SIZE,
+                <&'a [V] as crate::types::SizedOnDisk>::FIXED_SIZE,
+                <I::Item as crate::types::SizedOnDisk>::FIXED_SIZE,
+            ],
+        ),
+        "`marker` has no fixed offset",
+    );
+    ///The encoded size of `marker`.
+    pub const SIZE_MARKER: usize = match <std::marker::PhantomData<
+        M,
+    > as crate::types::SizedOnDisk>::FIXED_SIZE {
+        ::core::option::Option::Some(size) => size,
+        ::core::option::Option::None => ::core::panic!("`marker` has no fixed size"),
+    };
+    /// A description of the encoding of this type.
+    pub fn schema() -> &'static crate::types::Schema {
+        const {
+            &crate::types::Schema {
+                name: "Table",
+                layout: crate::types::schema::Layout::Struct(
+                    &[
+                        crate::types::schema::Field {
+                            name: "keys",
+                            ty: "Vec<K>",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <Vec<K> as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "values",
+                            ty: "&'a[V]",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <&'a [V] as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "first",
+                            ty: "I::Item",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <I::Item as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                        crate::types::schema::Field {
+                            name: "marker",
+                            ty: "std::marker::PhantomData<M>",
+                            ignored: false,
+                            options: crate::types::Options::DEFAULT,
+                            align: ::core::option::Option::None,
+                            since: ::core::option::Option::None,
+                            until: ::core::option::Option::None,
+                            bits: ::core::option::Option::None,
+                            custom: false,
+                            fixed_size: <std::marker::PhantomData<
+                                M,
+                            > as crate::types::SizedOnDisk>::FIXED_SIZE,
+                        },
+                    ],
+                ),
+                fixed_size: <Self as crate::types::SizedOnDisk>::FIXED_SIZE,
+                version: ::core::option::Option::None,
+                checksum: ::core::option::Option::None,
+                align: ::core::option::Option::None,
+                transparent: false,
+            }
+        }
+    }
+}
+impl<'a, K: Ord, V, I: Iterator, M> crate::types::SizedOnDisk for Table<'a, K, V, I, M>
+where
+    V: Clone,
+    K: crate::types::SizedOnDisk,
+    V: crate::types::SizedOnDisk,
+    I::Item: crate::types::SizedOnDisk,
+{
+    const FIXED_SIZE: ::core::option::Option<usize> = crate::types::__private::fixed_sum(
+        &[
+            <Vec<K> as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <&'a [V] as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <I::Item as crate::types::SizedOnDisk>::FIXED_SIZE,
+            <std::marker::PhantomData<M> as crate::types::SizedOnDisk>::FIXED_SIZE,
+        ],
+    );
+    fn size(&self) -> usize {
+        Self::size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn size_in(&self, options: crate::types::Options) -> usize {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return size;
+        }
+        0 + <Vec<K> as crate::types::SizedOnDisk>::size_in(&self.keys, options)
+            + <&'a [V] as crate::types::SizedOnDisk>::size_in(&self.values, options)
+            + <I::Item as crate::types::SizedOnDisk>::size_in(&self.first, options)
+            + <std::marker::PhantomData<
+                M,
+            > as crate::types::SizedOnDisk>::size_in(&self.marker, options)
+    }
+    fn checked_size(&self) -> ::core::option::Option<usize> {
+        Self::checked_size_in(self, crate::types::Options::DEFAULT)
+    }
+    fn checked_size_in(
+        &self,
+        options: crate::types::Options,
+    ) -> ::core::option::Option<usize> {
+        if let ::core::option::Option::Some(size) = crate::types::__private::fixed_in(
+            Self::FIXED_SIZE,
+            options,
+        ) {
+            return ::core::option::Option::Some(size);
+        }
+        ::core::option::Option::Some({
+            let mut __n: usize = 0;
+            __n = __n
+                .checked_add(
+                    <Vec<
+                        K,
+                    > as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.keys,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <&'a [V] as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.values,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <I::Item as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.first,
+                        options,
+                    )?,
+                )?;
+            __n = __n
+                .checked_add(
+                    <std::marker::PhantomData<
+                        M,
+                    > as crate::types::SizedOnDisk>::checked_size_in(
+                        &self.marker,
+                        options,
+                    )?,
+                )?;
+            __n
+        })
+    }
+}
@@ -0,0 +1,10 @@
+// The inferred bounds are appended to the type's own where clause.
+struct Table<'a, K: Ord, V, I: Iterator, M>
+where
+    V: Clone,
+{
+    keys: Vec<K>,
+    values: &'a [V],
+    first: I::Item,
+    marker: std::marker::PhantomData<M>,
+}